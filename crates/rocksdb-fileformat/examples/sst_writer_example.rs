@@ -14,6 +14,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         block_size: 4096,
         block_restart_interval: 16,
         format_version: FormatVersion::V5,
+        compression_dict_size: 0,
+        compression_dict_sample_budget: 0,
+        enable_file_integrity_digest: false,
     };
 
     // Create and use the writer
@@ -1,11 +1,105 @@
 use crate::block_handle::BlockHandle;
-use crate::compression::compress;
-use crate::error::Result;
-use crate::types::{ChecksumType, CompressionType, checksum_modifier_for_context};
+use crate::compression::{compress, compress_by_id};
+use crate::compressor::CompressorRegistry;
+use crate::encryption::{iv_for_offset, Encryptor};
+use crate::error::{Error, Result};
+use crate::types::{ChecksumType, CompressionType};
 use byteorder::{LittleEndian, WriteBytesExt};
+use std::sync::Arc;
+
+/// Checksum a to-be-written trailer's preceding bytes (block data or index
+/// data, plus the compression-type byte), applying the format_version >= 6
+/// context-checksum modifier when both `file_offset` and
+/// `base_context_checksum` are present. Shared by [`DataBlockBuilder::finish`]
+/// and [`IndexBlockBuilder::finish`] so the two don't drift, and reused by
+/// [`crate::builder::SstImageBuilder`] for test-assembled raw meta blocks.
+pub(crate) fn checksum_for_trailer(
+    checksum_type: ChecksumType,
+    data: &[u8],
+    file_offset: Option<u64>,
+    base_context_checksum: Option<u32>,
+) -> u32 {
+    match (file_offset, base_context_checksum) {
+        (Some(offset), Some(base_checksum)) => {
+            checksum_type.calculate_with_context(data, base_checksum, offset)
+        }
+        _ => checksum_type.calculate(data),
+    }
+}
+
+/// Set in the top bit of a data block's restart-count field to flag that the
+/// restart array is stored delta-and-bitpacked (see [`DataBlockBuilderOptions::compact_restarts`])
+/// rather than as a plain array of `u32`s. A restart count never comes close
+/// to `i32::MAX` restart points in practice, so the bit is free to repurpose
+/// here the same way the plain count field is already the last thing written
+/// before the block trailer.
+pub(crate) const COMPACT_RESTARTS_FLAG: u32 = 0x8000_0000;
+
+pub(crate) fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+pub(crate) fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn bits_required(value: u64) -> u8 {
+    if value == 0 {
+        1
+    } else {
+        (64 - value.leading_zeros()) as u8
+    }
+}
+
+/// Pack `values` (each fitting in `bit_width` bits) LSB-first into as few
+/// bytes as possible. Paired with [`bitunpack_lsb`].
+pub(crate) fn bitpack_lsb(values: &[u64], bit_width: u8) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut acc: u64 = 0;
+    let mut acc_bits: u32 = 0;
+    for &value in values {
+        acc |= value << acc_bits;
+        acc_bits += bit_width as u32;
+        while acc_bits >= 8 {
+            out.push((acc & 0xFF) as u8);
+            acc >>= 8;
+            acc_bits -= 8;
+        }
+    }
+    if acc_bits > 0 {
+        out.push((acc & 0xFF) as u8);
+    }
+    out
+}
+
+/// Inverse of [`bitpack_lsb`]: unpack `count` values of `bit_width` bits each
+/// back out of `bytes`.
+pub(crate) fn bitunpack_lsb(bytes: &[u8], count: usize, bit_width: u8) -> Vec<u64> {
+    let mask: u64 = if bit_width >= 64 {
+        u64::MAX
+    } else {
+        (1u64 << bit_width) - 1
+    };
+
+    let mut out = Vec::with_capacity(count);
+    let mut acc: u64 = 0;
+    let mut acc_bits: u32 = 0;
+    let mut byte_iter = bytes.iter();
+    for _ in 0..count {
+        while acc_bits < bit_width as u32 {
+            let byte = *byte_iter.next().unwrap_or(&0);
+            acc |= (byte as u64) << acc_bits;
+            acc_bits += 8;
+        }
+        out.push(acc & mask);
+        acc >>= bit_width as u32;
+        acc_bits -= bit_width as u32;
+    }
+    out
+}
 
 /// Configuration options for DataBlockBuilder
-#[derive(Debug, Clone)]
+#[derive(Clone, Default)]
 pub struct DataBlockBuilderOptions {
     /// Number of entries between restart points for prefix compression
     pub restart_interval: usize,
@@ -13,15 +107,28 @@ pub struct DataBlockBuilderOptions {
     pub block_size_target: Option<usize>,
     /// Whether to enable checksum verification (for future use)
     pub enable_checksums: bool,
+    /// When set, [`DataBlockBuilder::finish`] encrypts the compressed block
+    /// with this [`Encryptor`] before appending the trailer, using an IV
+    /// derived from the block's `file_offset` (see [`iv_for_offset`]).
+    pub encryptor: Option<Arc<dyn Encryptor>>,
+    /// When set, the restart array is stored as a base offset plus
+    /// zigzag-delta values bit-packed to their minimum width, instead of one
+    /// raw `u32` per restart point. Shrinks per-block overhead for blocks
+    /// with a small `restart_interval` (and so many restart points), at the
+    /// cost of the LevelDB-compatible plain layout; off by default so
+    /// existing readers of the plain format keep working unchanged.
+    pub compact_restarts: bool,
 }
 
-impl Default for DataBlockBuilderOptions {
-    fn default() -> Self {
-        Self {
-            restart_interval: 16,
-            block_size_target: None,
-            enable_checksums: false,
-        }
+impl std::fmt::Debug for DataBlockBuilderOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataBlockBuilderOptions")
+            .field("restart_interval", &self.restart_interval)
+            .field("block_size_target", &self.block_size_target)
+            .field("enable_checksums", &self.enable_checksums)
+            .field("encryptor", &self.encryptor.is_some())
+            .field("compact_restarts", &self.compact_restarts)
+            .finish()
     }
 }
 
@@ -43,6 +150,19 @@ impl DataBlockBuilderOptions {
         self.enable_checksums = enable;
         self
     }
+
+    /// Encrypt every block this builder finishes with `encryptor`.
+    pub fn with_encryptor(mut self, encryptor: Arc<dyn Encryptor>) -> Self {
+        self.encryptor = Some(encryptor);
+        self
+    }
+
+    /// Store the restart array delta-and-bitpacked instead of as plain
+    /// `u32`s. See [`Self::compact_restarts`].
+    pub fn with_compact_restarts(mut self, enable: bool) -> Self {
+        self.compact_restarts = enable;
+        self
+    }
 }
 
 /// Builder for data blocks with prefix compression and restart points
@@ -92,10 +212,10 @@ impl DataBlockBuilder {
 
         let non_shared = key.len() - shared;
 
-        // Encode entry: shared_length(varint) non_shared_length(varint) value_length(varint) key_delta value
+        // Encode entry: shared_length(varint) non_shared_length(varint) value_length(varint64) key_delta value
         self.encode_varint(shared as u32);
         self.encode_varint(non_shared as u32);
-        self.encode_varint(value.len() as u32);
+        self.encode_varint64(value.len() as u64);
 
         // Add key delta
         self.buffer.extend_from_slice(&key[shared..]);
@@ -121,56 +241,115 @@ impl DataBlockBuilder {
         }
         self.finished = true;
 
-        // Add restart array
-        for restart in &self.restarts {
-            self.buffer.write_u32::<LittleEndian>(*restart).unwrap();
-        }
+        self.write_restarts();
 
-        // Add restart count
-        self.buffer
-            .write_u32::<LittleEndian>(self.restarts.len() as u32)
-            .unwrap();
+        // Compress the data (without the trailer)
+        let data = if compression_type == CompressionType::None {
+            self.buffer.clone()
+        } else {
+            compress(&self.buffer, compression_type)?
+        };
+
+        // Encrypt the compressed block before the trailer is attached, so the
+        // checksum below covers the ciphertext and a corrupted or truncated
+        // ciphertext is caught before it's ever handed to an `Encryptor`.
+        let data = match &self.options.encryptor {
+            Some(encryptor) => {
+                let file_offset = file_offset.ok_or_else(|| {
+                    Error::InvalidArgument(
+                        "file_offset is required when an encryptor is configured, to guarantee a unique IV per block".to_string(),
+                    )
+                })?;
+                let iv = iv_for_offset(file_offset);
+                encryptor.encrypt(&data, &iv)?
+            }
+            None => data,
+        };
 
-        // Calculate checksum over the block data + compression type
-        let mut checksum_data = self.buffer.clone();
+        // Calculate checksum over the (possibly compressed, possibly
+        // encrypted) block data + compression type
+        let mut checksum_data = data.clone();
         checksum_data.push(compression_type as u8);
-        let mut checksum = checksum_type.calculate(&checksum_data);
+        let checksum = checksum_for_trailer(checksum_type, &checksum_data, file_offset, base_context_checksum);
+
+        let mut result = data;
+        result.push(compression_type as u8);
+        result.write_u32::<LittleEndian>(checksum).unwrap();
+        Ok(result)
+    }
 
-        // Apply context-based checksum modification if needed
-        if let (Some(offset), Some(base_checksum)) = (file_offset, base_context_checksum) {
-            let modifier = checksum_modifier_for_context(base_checksum, offset);
-            checksum = checksum.wrapping_add(modifier);
+    /// Like [`Self::finish`], but compress with a custom codec registered
+    /// under `id` in `registry` (see [`CompressorRegistry::register`])
+    /// instead of one of the built-in [`CompressionType`] variants. `id` is
+    /// still the one-byte marker written into the trailer, and the checksum
+    /// still covers `compressed_data + id` exactly as [`Self::finish`]
+    /// computes it over `compressed_data + compression_type`. Does not apply
+    /// [`DataBlockBuilderOptions::encryptor`] — registry-based compression
+    /// and per-block encryption are independent extension points and are not
+    /// currently combined in one path.
+    pub fn finish_with_registry(
+        &mut self,
+        id: u8,
+        registry: &CompressorRegistry,
+        checksum_type: ChecksumType,
+        file_offset: Option<u64>,
+        base_context_checksum: Option<u32>,
+    ) -> Result<Vec<u8>> {
+        if self.finished {
+            panic!("DataBlockBuilder already finished");
         }
+        self.finished = true;
+
+        self.write_restarts();
+
+        let compressed_data = compress_by_id(&self.buffer, id, registry)?;
+
+        let mut checksum_data = compressed_data.clone();
+        checksum_data.push(id);
+        let checksum = checksum_for_trailer(checksum_type, &checksum_data, file_offset, base_context_checksum);
 
-        // For uncompressed blocks
-        if compression_type == CompressionType::None {
-            let mut result = self.buffer.clone();
-            result.push(compression_type as u8);
-            result.write_u32::<LittleEndian>(checksum).unwrap();
-            Ok(result)
+        let mut result = compressed_data;
+        result.push(id);
+        result.write_u32::<LittleEndian>(checksum).unwrap();
+
+        Ok(result)
+    }
+
+    /// Write the restart array to `self.buffer`, in either the plain
+    /// fixed-width layout or, when
+    /// [`DataBlockBuilderOptions::compact_restarts`] is set and there's more
+    /// than one restart point to gain from it, a base offset plus
+    /// zigzag-delta values bit-packed to their minimum width. The format
+    /// actually used is flagged in the restart-count field via
+    /// [`COMPACT_RESTARTS_FLAG`] so [`crate::data_block::DataBlock`] can tell
+    /// the two apart. Shared by [`Self::finish`] and
+    /// [`Self::finish_with_registry`] so the two restart encodings don't
+    /// drift.
+    fn write_restarts(&mut self) {
+        if self.options.compact_restarts && self.restarts.len() > 1 {
+            let base = self.restarts[0];
+            self.buffer.write_u32::<LittleEndian>(base).unwrap();
+
+            let deltas: Vec<u64> = self
+                .restarts
+                .windows(2)
+                .map(|pair| zigzag_encode(pair[1] as i64 - pair[0] as i64))
+                .collect();
+            let bit_width = deltas.iter().copied().map(bits_required).max().unwrap_or(1);
+            let packed = bitpack_lsb(&deltas, bit_width);
+
+            self.buffer.extend_from_slice(&packed);
+            self.buffer.push(bit_width);
+            self.buffer
+                .write_u32::<LittleEndian>(self.restarts.len() as u32 | COMPACT_RESTARTS_FLAG)
+                .unwrap();
         } else {
-            // Compress the data (without the trailer)
-            let compressed_data = compress(&self.buffer, compression_type)?;
-
-            // Recalculate checksum over compressed data + compression type
-            let mut compressed_checksum_data = compressed_data.clone();
-            compressed_checksum_data.push(compression_type as u8);
-            let mut compressed_checksum = checksum_type.calculate(&compressed_checksum_data);
-
-            // Apply context-based checksum modification if needed
-            if let (Some(offset), Some(base_checksum)) = (file_offset, base_context_checksum) {
-                let modifier = checksum_modifier_for_context(base_checksum, offset);
-                compressed_checksum = compressed_checksum.wrapping_add(modifier);
+            for restart in &self.restarts {
+                self.buffer.write_u32::<LittleEndian>(*restart).unwrap();
             }
-
-            // Add the trailer after compression
-            let mut result = compressed_data;
-            result.push(compression_type as u8);
-            result
-                .write_u32::<LittleEndian>(compressed_checksum)
+            self.buffer
+                .write_u32::<LittleEndian>(self.restarts.len() as u32)
                 .unwrap();
-
-            Ok(result)
         }
     }
 
@@ -188,7 +367,45 @@ impl DataBlockBuilder {
     }
 
     pub fn size_estimate(&self) -> usize {
-        self.buffer.len() + 4 * self.restarts.len() + 4 + 5 // restarts + count + trailer
+        // restarts + count + trailer, plus a small padding allowance when an
+        // encryptor is configured — some stream ciphers expand their output
+        // (e.g. an AEAD tag), so callers sizing a block against a target
+        // should not assume ciphertext length equals plaintext length.
+        let encryption_padding = if self.options.encryptor.is_some() { 16 } else { 0 };
+        self.buffer.len() + 4 * self.restarts.len() + 4 + 5 + encryption_padding
+    }
+
+    /// Returns true once adding `next_key`/`next_value` would push this
+    /// block past [`DataBlockBuilderOptions::block_size_target`], so the SST
+    /// writer can cut a roughly uniform-sized block here instead of relying
+    /// solely on `restart_interval`. Only considered at a restart boundary —
+    /// either the builder just restarted (`counter == 0`, so there's no
+    /// in-progress prefix-compression run to interrupt) or `add` will
+    /// restart on its own on the very next call (`counter ==
+    /// restart_interval`, so `next_key` gets no prefix-compression benefit
+    /// regardless of which block it lands in). Returns false when no
+    /// `block_size_target` is configured or the block is still empty.
+    pub fn should_finish(&self, next_key: &[u8], next_value: &[u8]) -> bool {
+        let Some(target) = self.options.block_size_target else {
+            return false;
+        };
+
+        if self.empty() {
+            return false;
+        }
+
+        let at_restart_boundary =
+            self.counter == 0 || self.counter == self.options.restart_interval;
+        if !at_restart_boundary {
+            return false;
+        }
+
+        // A restarted entry always has shared = 0, so its encoded overhead
+        // is the full key/value bytes plus three varints (shared=0,
+        // non_shared, value length — up to 10 bytes each in the worst case).
+        let estimated_next_entry_size = 3 * 10 + next_key.len() + next_value.len();
+
+        self.size_estimate() + estimated_next_entry_size > target
     }
 
     fn encode_varint(&mut self, mut value: u32) {
@@ -198,6 +415,18 @@ impl DataBlockBuilder {
         }
         self.buffer.push(value as u8);
     }
+
+    /// Like [`Self::encode_varint`], but for a 64-bit value — used for value
+    /// lengths, since a value (unlike a key) can plausibly exceed
+    /// `u32::MAX` bytes and silently truncating its length with an `as u32`
+    /// cast would corrupt the block.
+    fn encode_varint64(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buffer.push((value & 0x7F) as u8 | 0x80);
+            value >>= 7;
+        }
+        self.buffer.push(value as u8);
+    }
 }
 
 /// Builder for index blocks that track data block locations
@@ -208,6 +437,7 @@ pub struct IndexBlockBuilder {
     restart_interval: usize,
     last_key: Vec<u8>,
     finished: bool,
+    encryptor: Option<Arc<dyn Encryptor>>,
 }
 
 impl IndexBlockBuilder {
@@ -219,6 +449,7 @@ impl IndexBlockBuilder {
             restart_interval,
             last_key: Vec::new(),
             finished: false,
+            encryptor: None,
         };
 
         // Add first restart point
@@ -226,6 +457,16 @@ impl IndexBlockBuilder {
         builder
     }
 
+    /// Encrypt every block this builder finishes with `encryptor`, the same
+    /// way [`DataBlockBuilderOptions::with_encryptor`] does for data blocks.
+    /// `IndexBlockBuilder::new` takes a bare `restart_interval` rather than
+    /// an options struct, so this is a chaining setter instead of a
+    /// constructor argument.
+    pub fn with_encryptor(mut self, encryptor: Arc<dyn Encryptor>) -> Self {
+        self.encryptor = Some(encryptor);
+        self
+    }
+
     pub fn add_index_entry(&mut self, key: &[u8], block_handle: &BlockHandle) {
         assert!(!self.finished);
         assert!(self.counter <= self.restart_interval);
@@ -246,10 +487,12 @@ impl IndexBlockBuilder {
 
         let non_shared = key.len() - shared;
 
-        // Encode block handle as value
+        // Encode block handle as value. Offset and size are varint64 (not
+        // truncated to u32) so a table larger than 4 GiB still gets a
+        // correct index.
         let mut handle_data = Vec::new();
-        self.encode_varint_to(&mut handle_data, block_handle.offset as u32);
-        self.encode_varint_to(&mut handle_data, block_handle.size as u32);
+        self.encode_varint_to64(&mut handle_data, block_handle.offset);
+        self.encode_varint_to64(&mut handle_data, block_handle.size);
 
         // Encode entry: shared_length(varint) non_shared_length(varint) value_length(varint) key_delta block_handle
         self.encode_varint(shared as u32);
@@ -290,47 +533,79 @@ impl IndexBlockBuilder {
             .write_u32::<LittleEndian>(self.restarts.len() as u32)
             .unwrap();
 
-        // Calculate checksum over the block data + compression type
-        let mut checksum_data = self.buffer.clone();
+        // Compress the data (without the trailer)
+        let data = if compression_type == CompressionType::None {
+            self.buffer.clone()
+        } else {
+            compress(&self.buffer, compression_type)?
+        };
+
+        // Encrypt the compressed block before the trailer is attached, so the
+        // checksum below covers the ciphertext.
+        let data = match &self.encryptor {
+            Some(encryptor) => {
+                let file_offset = file_offset.ok_or_else(|| {
+                    Error::InvalidArgument(
+                        "file_offset is required when an encryptor is configured, to guarantee a unique IV per block".to_string(),
+                    )
+                })?;
+                let iv = iv_for_offset(file_offset);
+                encryptor.encrypt(&data, &iv)?
+            }
+            None => data,
+        };
+
+        // Calculate checksum over the (possibly compressed, possibly
+        // encrypted) block data + compression type
+        let mut checksum_data = data.clone();
         checksum_data.push(compression_type as u8);
-        let mut checksum = checksum_type.calculate(&checksum_data);
+        let checksum = checksum_for_trailer(checksum_type, &checksum_data, file_offset, base_context_checksum);
+
+        let mut result = data;
+        result.push(compression_type as u8);
+        result.write_u32::<LittleEndian>(checksum).unwrap();
+        Ok(result)
+    }
+
+    /// Like [`Self::finish`], but compress with a custom codec registered
+    /// under `id` in `registry` (see [`CompressorRegistry::register`])
+    /// instead of one of the built-in [`CompressionType`] variants. `id` is
+    /// still the one-byte marker written into the trailer, and the checksum
+    /// still covers `compressed_data + id` exactly as [`Self::finish`]
+    /// computes it over `compressed_data + compression_type`. Does not apply
+    /// an [`Encryptor`] set via [`Self::with_encryptor`] — see the identical
+    /// note on [`DataBlockBuilder::finish_with_registry`].
+    pub fn finish_with_registry(
+        &mut self,
+        id: u8,
+        registry: &CompressorRegistry,
+        checksum_type: ChecksumType,
+        file_offset: Option<u64>,
+        base_context_checksum: Option<u32>,
+    ) -> Result<Vec<u8>> {
+        if self.finished {
+            panic!("IndexBlockBuilder already finished");
+        }
+        self.finished = true;
 
-        // Apply context-based checksum modification if needed
-        if let (Some(offset), Some(base_checksum)) = (file_offset, base_context_checksum) {
-            let modifier = checksum_modifier_for_context(base_checksum, offset);
-            checksum = checksum.wrapping_add(modifier);
+        for restart in &self.restarts {
+            self.buffer.write_u32::<LittleEndian>(*restart).unwrap();
         }
+        self.buffer
+            .write_u32::<LittleEndian>(self.restarts.len() as u32)
+            .unwrap();
 
-        // For uncompressed blocks
-        if compression_type == CompressionType::None {
-            let mut result = self.buffer.clone();
-            result.push(compression_type as u8);
-            result.write_u32::<LittleEndian>(checksum).unwrap();
-            Ok(result)
-        } else {
-            // Compress the data (without the trailer)
-            let compressed_data = compress(&self.buffer, compression_type)?;
-
-            // Recalculate checksum over compressed data + compression type
-            let mut compressed_checksum_data = compressed_data.clone();
-            compressed_checksum_data.push(compression_type as u8);
-            let mut compressed_checksum = checksum_type.calculate(&compressed_checksum_data);
-
-            // Apply context-based checksum modification if needed
-            if let (Some(offset), Some(base_checksum)) = (file_offset, base_context_checksum) {
-                let modifier = checksum_modifier_for_context(base_checksum, offset);
-                compressed_checksum = compressed_checksum.wrapping_add(modifier);
-            }
+        let compressed_data = compress_by_id(&self.buffer, id, registry)?;
 
-            // Add the trailer after compression
-            let mut result = compressed_data;
-            result.push(compression_type as u8);
-            result
-                .write_u32::<LittleEndian>(compressed_checksum)
-                .unwrap();
+        let mut checksum_data = compressed_data.clone();
+        checksum_data.push(id);
+        let checksum = checksum_for_trailer(checksum_type, &checksum_data, file_offset, base_context_checksum);
 
-            Ok(result)
-        }
+        let mut result = compressed_data;
+        result.push(id);
+        result.write_u32::<LittleEndian>(checksum).unwrap();
+
+        Ok(result)
     }
 
     pub fn empty(&self) -> bool {
@@ -345,7 +620,9 @@ impl IndexBlockBuilder {
         self.buffer.push(value as u8);
     }
 
-    fn encode_varint_to(&self, buffer: &mut Vec<u8>, mut value: u32) {
+    /// Varint-encode a block-handle offset or size into `buffer` — 64-bit
+    /// since either can exceed `u32::MAX` for a table larger than 4 GiB.
+    fn encode_varint_to64(&self, buffer: &mut Vec<u8>, mut value: u64) {
         while value >= 0x80 {
             buffer.push((value & 0x7F) as u8 | 0x80);
             value >>= 7;
@@ -357,7 +634,9 @@ impl IndexBlockBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::block_trailer::BLOCK_TRAILER_SIZE;
     use crate::types::{ChecksumType, CompressionType};
+    use byteorder::ByteOrder;
 
     #[test]
     fn test_data_block_builder_simple() -> Result<()> {
@@ -430,4 +709,251 @@ mod tests {
         assert!(builder.empty());
         Ok(())
     }
+
+    struct Xor(u8);
+
+    impl crate::compressor::Compressor for Xor {
+        fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(data.iter().map(|b| b ^ self.0).collect())
+        }
+
+        fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+            self.compress(data)
+        }
+    }
+
+    #[test]
+    fn test_data_block_builder_finish_with_registry_writes_the_custom_id_into_the_trailer() -> Result<()>
+    {
+        let mut registry = crate::compressor::CompressorRegistry::new();
+        registry.register(200, Xor(0x42));
+
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(16));
+        builder.add(b"key1", b"value1");
+
+        let block_data =
+            builder.finish_with_registry(200, &registry, ChecksumType::CRC32c, None, None)?;
+        assert_eq!(block_data[block_data.len() - 5], 200);
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_block_builder_finish_with_registry_writes_the_custom_id_into_the_trailer() -> Result<()>
+    {
+        let mut registry = crate::compressor::CompressorRegistry::new();
+        registry.register(200, Xor(0x42));
+
+        let mut builder = IndexBlockBuilder::new(16);
+        builder.add_index_entry(
+            b"key1",
+            &BlockHandle {
+                offset: 0,
+                size: 100,
+            },
+        );
+
+        let block_data =
+            builder.finish_with_registry(200, &registry, ChecksumType::CRC32c, None, None)?;
+        assert_eq!(block_data[block_data.len() - 5], 200);
+        Ok(())
+    }
+
+    struct XorEncryptor(u8);
+
+    impl crate::encryption::Encryptor for XorEncryptor {
+        fn encrypt(&self, data: &[u8], iv: &[u8; 12]) -> Result<Vec<u8>> {
+            Ok(data
+                .iter()
+                .enumerate()
+                .map(|(i, b)| b ^ self.0 ^ iv[i % iv.len()])
+                .collect())
+        }
+
+        fn decrypt(&self, data: &[u8], iv: &[u8; 12]) -> Result<Vec<u8>> {
+            self.encrypt(data, iv)
+        }
+    }
+
+    #[test]
+    fn test_data_block_builder_finish_encrypts_before_the_trailer() -> Result<()> {
+        let mut plain_builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(16));
+        plain_builder.add(b"key1", b"value1");
+        let plain_block =
+            plain_builder.finish(CompressionType::None, ChecksumType::CRC32c, Some(0), None)?;
+
+        let mut encrypted_builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(16)
+                .with_encryptor(Arc::new(XorEncryptor(0x5A))),
+        );
+        encrypted_builder.add(b"key1", b"value1");
+        let encrypted_block = encrypted_builder.finish(
+            CompressionType::None,
+            ChecksumType::CRC32c,
+            Some(0),
+            None,
+        )?;
+
+        // Same length (the stub cipher doesn't expand), different bytes, and
+        // the trailer's compression-type byte is left untouched since only
+        // the block data itself is encrypted.
+        assert_eq!(plain_block.len(), encrypted_block.len());
+        assert_ne!(plain_block, encrypted_block);
+        assert_eq!(
+            plain_block[plain_block.len() - 5],
+            encrypted_block[encrypted_block.len() - 5]
+        );
+
+        let encryptor = XorEncryptor(0x5A);
+        let iv = iv_for_offset(0);
+        let ciphertext = &encrypted_block[..encrypted_block.len() - BLOCK_TRAILER_SIZE as usize];
+        let decrypted = encryptor.decrypt(ciphertext, &iv)?;
+        let plaintext = &plain_block[..plain_block.len() - BLOCK_TRAILER_SIZE as usize];
+        assert_eq!(decrypted, plaintext);
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_block_builder_finish_rejects_missing_offset_with_encryptor() {
+        let mut builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(16)
+                .with_encryptor(Arc::new(XorEncryptor(0x5A))),
+        );
+        builder.add(b"key1", b"value1");
+        let err = builder
+            .finish(CompressionType::None, ChecksumType::CRC32c, None, None)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn test_index_block_builder_with_encryptor_round_trips() -> Result<()> {
+        let mut builder =
+            IndexBlockBuilder::new(16).with_encryptor(Arc::new(XorEncryptor(0x11)));
+        builder.add_index_entry(
+            b"key1",
+            &BlockHandle {
+                offset: 0,
+                size: 100,
+            },
+        );
+
+        let block_data =
+            builder.finish(CompressionType::None, ChecksumType::CRC32c, Some(42), None)?;
+
+        let encryptor = XorEncryptor(0x11);
+        let iv = iv_for_offset(42);
+        let ciphertext = &block_data[..block_data.len() - BLOCK_TRAILER_SIZE as usize];
+        let decrypted = encryptor.decrypt(ciphertext, &iv)?;
+        assert!(!decrypted.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_block_builder_finish_rejects_missing_offset_with_encryptor() {
+        let mut builder = IndexBlockBuilder::new(16).with_encryptor(Arc::new(XorEncryptor(0x11)));
+        builder.add_index_entry(
+            b"key1",
+            &BlockHandle {
+                offset: 0,
+                size: 100,
+            },
+        );
+        let err = builder
+            .finish(CompressionType::None, ChecksumType::CRC32c, None, None)
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn test_should_finish_is_false_without_a_block_size_target() {
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(16));
+        builder.add(b"key1", b"value1");
+        assert!(!builder.should_finish(b"key2", b"value2"));
+    }
+
+    #[test]
+    fn test_should_finish_is_false_when_empty() {
+        let builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(16)
+                .with_block_size_target(1),
+        );
+        assert!(!builder.should_finish(b"key1", b"value1"));
+    }
+
+    #[test]
+    fn test_should_finish_true_once_target_exceeded_at_a_restart_boundary() {
+        let mut builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(1)
+                .with_block_size_target(32),
+        );
+        builder.add(b"key1", b"value1");
+        // restart_interval of 1 means counter == restart_interval here, a
+        // restart boundary, and the block is already near the tiny target.
+        assert!(builder.should_finish(b"key2", b"value2"));
+    }
+
+    #[test]
+    fn test_should_finish_false_mid_prefix_compression_run_even_past_target() {
+        let mut builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(16)
+                .with_block_size_target(1),
+        );
+        builder.add(b"key1", b"value1");
+        // counter is 1, short of restart_interval (16), so this isn't a
+        // restart boundary yet even though the tiny target is long exceeded.
+        assert!(!builder.should_finish(b"key2", b"value2"));
+    }
+
+    #[test]
+    fn test_bitpack_lsb_round_trips_through_bitunpack_lsb() {
+        let values: Vec<u64> = vec![0, 1, 3, 7, 5, 31, 17];
+        let bit_width = values.iter().copied().map(bits_required).max().unwrap();
+        let packed = bitpack_lsb(&values, bit_width);
+        let unpacked = bitunpack_lsb(&packed, values.len(), bit_width);
+        assert_eq!(unpacked, values);
+    }
+
+    #[test]
+    fn test_zigzag_round_trips_positive_and_negative() {
+        for value in [0i64, 1, -1, 42, -42, i32::MAX as i64, i32::MIN as i64] {
+            assert_eq!(zigzag_decode(zigzag_encode(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_data_block_builder_finish_with_compact_restarts_round_trips() -> Result<()> {
+        let mut builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(1)
+                .with_compact_restarts(true),
+        );
+        builder.add(b"key1", b"value1");
+        builder.add(b"key2", b"value2");
+        builder.add(b"key3", b"value3");
+
+        let block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let trailing_count = LittleEndian::read_u32(&block_data[block_data.len() - 5 - 4..]);
+        assert_ne!(trailing_count & COMPACT_RESTARTS_FLAG, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_data_block_builder_size_estimate_adds_padding_when_encrypted() {
+        let plain =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(16));
+        let encrypted = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(16)
+                .with_encryptor(Arc::new(XorEncryptor(0x5A))),
+        );
+        assert_eq!(encrypted.size_estimate(), plain.size_estimate() + 16);
+    }
 }
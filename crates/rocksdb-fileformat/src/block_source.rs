@@ -0,0 +1,108 @@
+// Copyright 2024 YaleDB Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::{Error, Result};
+
+/// A pluggable random-access byte source that `SstReader` operates on.
+///
+/// This decouples the SST format logic from any particular container, so the
+/// same reader can walk a file on disk, an in-memory buffer, a memory-mapped
+/// region (`crate::mmap_source::MmapFileSource`, behind the `mmap` feature),
+/// or (in the future) a ranged object-store fetch.
+pub trait BlockSource {
+    /// Read exactly `buf.len()` bytes starting at `offset`, failing if the
+    /// source doesn't have that many bytes available.
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()>;
+
+    /// Total length of the source in bytes.
+    fn len(&self) -> u64;
+
+    /// Whether the source is empty.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl BlockSource for std::fs::File {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        use std::os::unix::fs::FileExt;
+        FileExt::read_exact_at(self, buf, offset)?;
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        self.metadata().map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+impl BlockSource for Vec<u8> {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        self.as_slice().read_at(offset, buf)
+    }
+
+    fn len(&self) -> u64 {
+        <[u8]>::len(self) as u64
+    }
+}
+
+impl BlockSource for [u8] {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        let start = offset as usize;
+        let end = start.checked_add(buf.len()).ok_or_else(|| {
+            Error::InvalidArgument("read_at offset overflow".to_string())
+        })?;
+
+        if end > self.len() {
+            return Err(Error::InvalidArgument(format!(
+                "read_at out of bounds: requested [{}, {}) but source is {} bytes",
+                start,
+                end,
+                self.len()
+            )));
+        }
+
+        buf.copy_from_slice(&self[start..end]);
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        <[u8]>::len(self) as u64
+    }
+}
+
+impl<T: BlockSource + ?Sized> BlockSource for &T {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        (**self).read_at(offset, buf)
+    }
+
+    fn len(&self) -> u64 {
+        (**self).len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slice_read_at() -> Result<()> {
+        let data = b"hello world".to_vec();
+        let mut buf = [0u8; 5];
+        data.read_at(6, &mut buf)?;
+        assert_eq!(&buf, b"world");
+        Ok(())
+    }
+
+    #[test]
+    fn test_slice_read_at_out_of_bounds() {
+        let data = b"hello".to_vec();
+        let mut buf = [0u8; 5];
+        assert!(data.read_at(3, &mut buf).is_err());
+    }
+
+    #[test]
+    fn test_slice_len() {
+        let data = b"hello".to_vec();
+        assert_eq!(BlockSource::len(&data), 5);
+    }
+}
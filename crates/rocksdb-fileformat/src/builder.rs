@@ -0,0 +1,380 @@
+// Copyright 2024 YaleDB Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A high-level assembler for complete, byte-exact in-memory SST images,
+//! for round-trip and fuzz testing of [`crate::footer::Footer::read_from`]
+//! and friends. Only available behind the `test-support` feature, since
+//! nothing outside tests should be constructing table files this way.
+//!
+//! ```ignore
+//! let mut image = SstImageBuilder::new(6, ChecksumType::CRC32c);
+//! let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![2])])?;
+//! let metaindex = image.add_metaindex(&[("rocksdb.properties", properties)])?;
+//! let bytes = image.finish(metaindex, BlockHandle::null())?;
+//! ```
+
+use crate::block_builder::{
+    checksum_for_trailer, DataBlockBuilder, DataBlockBuilderOptions, IndexBlockBuilder,
+};
+use crate::block_handle::BlockHandle;
+use crate::block_trailer::BLOCK_TRAILER_SIZE;
+use crate::compression::compress;
+use crate::error::Result;
+use crate::footer::Footer;
+use crate::types::{ChecksumType, CompressionType};
+
+/// Assembles an SST image one block at a time, tracking the running file
+/// offset so each block's trailer (and, for `format_version >= 6`, the
+/// footer itself) gets the correct context-checksum modifier.
+pub struct SstImageBuilder {
+    buffer: Vec<u8>,
+    checksum_type: ChecksumType,
+    compression_type: CompressionType,
+    format_version: u32,
+    base_context_checksum: u32,
+}
+
+impl SstImageBuilder {
+    /// `format_version >= 6` files get a random `base_context_checksum`
+    /// picked here, matching how a real writer picks one new value per file
+    /// rather than reusing a fixed constant (which would make two files with
+    /// identical block layouts produce identical checksums, defeating the
+    /// point of binding them to file position). Use
+    /// [`Self::with_base_context_checksum`] to pin a specific value for
+    /// deterministic tests.
+    pub fn new(format_version: u32, checksum_type: ChecksumType) -> Self {
+        let base_context_checksum = if format_version >= 6 {
+            rand::random::<u32>()
+        } else {
+            0
+        };
+
+        Self {
+            buffer: Vec::new(),
+            checksum_type,
+            compression_type: CompressionType::None,
+            format_version,
+            base_context_checksum,
+        }
+    }
+
+    pub fn with_compression(mut self, compression_type: CompressionType) -> Self {
+        self.compression_type = compression_type;
+        self
+    }
+
+    pub fn with_base_context_checksum(mut self, base_context_checksum: u32) -> Self {
+        self.base_context_checksum = base_context_checksum;
+        self
+    }
+
+    /// The offset the next block or the footer would land at.
+    pub fn current_offset(&self) -> u64 {
+        self.buffer.len() as u64
+    }
+
+    /// The image's bytes written so far, for tests that need to hash or
+    /// otherwise inspect a prefix of the file (e.g. a whole-file integrity
+    /// digest covering everything before the meta blocks that reference it)
+    /// before the image is finished.
+    pub fn bytes_so_far(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Append a block built from `entries` — the same shared-prefix,
+    /// restart-point format data, index, and metaindex blocks all use —
+    /// with a correct 5-byte trailer, returning the [`BlockHandle`] pointing
+    /// at it.
+    pub fn add_block(&mut self, entries: &[(Vec<u8>, Vec<u8>)]) -> Result<BlockHandle> {
+        let offset = self.current_offset();
+
+        let mut block =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(16));
+        for (key, value) in entries {
+            block.add(key, value);
+        }
+
+        let uses_context_checksum = self.format_version >= 6;
+        let block_bytes = block.finish(
+            self.compression_type,
+            self.checksum_type,
+            uses_context_checksum.then_some(offset),
+            uses_context_checksum.then_some(self.base_context_checksum),
+        )?;
+
+        let size = block_bytes.len() as u64 - BLOCK_TRAILER_SIZE;
+        self.buffer.extend_from_slice(&block_bytes);
+        Ok(BlockHandle::new(offset, size))
+    }
+
+    /// Append a block holding raw, already-encoded bytes rather than
+    /// KV-encoded entries — e.g. a `rocksdb.compression_dict` meta block,
+    /// which stores a plain dictionary blob, not restart-pointed key/value
+    /// pairs. Compressed with the builder's `compression_type` the same as
+    /// [`Self::add_block`], with a matching 5-byte trailer.
+    pub fn add_raw_block(&mut self, raw: &[u8]) -> Result<BlockHandle> {
+        let offset = self.current_offset();
+
+        let compressed = compress(raw, self.compression_type)?;
+        let mut block_bytes = compressed;
+        block_bytes.push(self.compression_type as u8);
+
+        let uses_context_checksum = self.format_version >= 6;
+        let checksum = checksum_for_trailer(
+            self.checksum_type,
+            &block_bytes,
+            uses_context_checksum.then_some(offset),
+            uses_context_checksum.then_some(self.base_context_checksum),
+        );
+        block_bytes.extend_from_slice(&checksum.to_le_bytes());
+
+        let size = block_bytes.len() as u64 - BLOCK_TRAILER_SIZE;
+        self.buffer.extend_from_slice(&block_bytes);
+        Ok(BlockHandle::new(offset, size))
+    }
+
+    /// Append a block whose bytes are already fully encoded — compressed and
+    /// trailer'd — such as the output of
+    /// [`crate::filter_block::FilterBlockBuilder::finish`], which (like
+    /// [`crate::block_builder::DataBlockBuilder::finish`] and
+    /// [`crate::block_builder::IndexBlockBuilder::finish`]) produces a
+    /// ready-to-place block on its own. Unlike [`Self::add_raw_block`], this
+    /// doesn't compress or checksum again; it just places the bytes and
+    /// returns the handle pointing at them.
+    pub fn add_finished_block(&mut self, block_bytes: &[u8]) -> BlockHandle {
+        let offset = self.current_offset();
+        let size = block_bytes.len() as u64 - BLOCK_TRAILER_SIZE;
+        self.buffer.extend_from_slice(block_bytes);
+        BlockHandle::new(offset, size)
+    }
+
+    /// Like [`Self::add_block`], but compresses `entries` under `id` via a
+    /// [`crate::compressor::CompressorRegistry`] holding `compressor`,
+    /// instead of one of the builder's built-in [`CompressionType`]s — for
+    /// testing registry-resolved reads of a data block compressed with a
+    /// foreign engine's custom codec id.
+    pub fn add_block_with_id(
+        &mut self,
+        entries: &[(Vec<u8>, Vec<u8>)],
+        id: u8,
+        compressor: impl crate::compressor::Compressor + 'static,
+    ) -> Result<BlockHandle> {
+        let offset = self.current_offset();
+
+        let mut registry = crate::compressor::CompressorRegistry::new();
+        registry.register(id, compressor);
+
+        let mut block =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(16));
+        for (key, value) in entries {
+            block.add(key, value);
+        }
+
+        let uses_context_checksum = self.format_version >= 6;
+        let block_bytes = block.finish_with_registry(
+            id,
+            &registry,
+            self.checksum_type,
+            uses_context_checksum.then_some(offset),
+            uses_context_checksum.then_some(self.base_context_checksum),
+        )?;
+
+        let size = block_bytes.len() as u64 - BLOCK_TRAILER_SIZE;
+        self.buffer.extend_from_slice(&block_bytes);
+        Ok(BlockHandle::new(offset, size))
+    }
+
+    /// Append an index block mapping each data block's last key to its
+    /// [`BlockHandle`], using the real production index-block encoding
+    /// ([`crate::block_builder::IndexBlockBuilder`]) rather than
+    /// [`Self::add_block`]'s generic shared-prefix format, so the image can
+    /// be read back through [`crate::iterator::SstTableIterator`].
+    pub fn add_index_block(&mut self, entries: &[(Vec<u8>, BlockHandle)]) -> Result<BlockHandle> {
+        let offset = self.current_offset();
+
+        let mut index = IndexBlockBuilder::new(usize::MAX);
+        for (key, handle) in entries {
+            index.add_index_entry(key, handle);
+        }
+
+        let uses_context_checksum = self.format_version >= 6;
+        let block_bytes = index.finish(
+            self.compression_type,
+            self.checksum_type,
+            uses_context_checksum.then_some(offset),
+            uses_context_checksum.then_some(self.base_context_checksum),
+        )?;
+
+        let size = block_bytes.len() as u64 - BLOCK_TRAILER_SIZE;
+        self.buffer.extend_from_slice(&block_bytes);
+        Ok(BlockHandle::new(offset, size))
+    }
+
+    /// Convenience for a metaindex block: each entry maps a meta-block name
+    /// to its already-written handle, encoded the same way
+    /// [`crate::metaindex::decode_metaindex`] expects (varint64 offset
+    /// followed by varint64 size).
+    pub fn add_metaindex(&mut self, entries: &[(&str, BlockHandle)]) -> Result<BlockHandle> {
+        let kvs = entries
+            .iter()
+            .map(|(name, handle)| Ok((name.as_bytes().to_vec(), handle.encode_to_bytes()?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.add_block(&kvs)
+    }
+
+    /// Finalize the image: append a footer of the builder's
+    /// `format_version` pointing at `metaindex_handle` (and `index_handle`,
+    /// ignored for `format_version >= 6` since the real footer layout has no
+    /// room for it there).
+    pub fn finish(mut self, metaindex_handle: BlockHandle, index_handle: BlockHandle) -> Result<Vec<u8>> {
+        let offset = self.current_offset();
+        let footer = Footer {
+            checksum_type: self.checksum_type,
+            metaindex_handle,
+            index_handle,
+            format_version: self.format_version,
+            base_context_checksum: (self.format_version >= 6).then_some(self.base_context_checksum),
+        };
+
+        let footer_bytes = footer.encode_to_bytes(offset)?;
+        self.buffer.extend_from_slice(&footer_bytes);
+        Ok(self.buffer)
+    }
+}
+
+/// Flip the last byte of an assembled image's magic number, so
+/// `Footer::read_from`/`read_from_source` see
+/// [`crate::error::Error::InvalidMagicNumber`].
+pub fn corrupt_magic(image: &mut [u8]) {
+    let last = image.len() - 1;
+    image[last] ^= 0xff;
+}
+
+/// Set a byte inside the v6+ footer's checked 8-byte reserved field to make
+/// it non-zero, so decoding sees
+/// [`crate::error::Error::ReservedFieldNonZero`]. Only meaningful for
+/// images finalized with `format_version >= 6` (a 53-byte footer).
+pub fn corrupt_v6_reserved_field(image: &mut [u8]) {
+    // From Footer::encode_to_bytes's v6+ layout, the checked reserved field
+    // sits 36 bytes before the end of the 53-byte footer.
+    let reserved_start = image.len() - 36;
+    image[reserved_start] |= 0x01;
+}
+
+/// Drop the last `drop_bytes` bytes of an assembled image, so
+/// `Footer::read_from`/`read_from_source` see a truncated footer
+/// ([`crate::error::Error::FileTooSmall`] when fewer than 48 bytes remain,
+/// otherwise typically [`crate::error::Error::InvalidMagicNumber`], since
+/// the image no longer ends in its magic number).
+pub fn truncate_footer(mut image: Vec<u8>, drop_bytes: usize) -> Vec<u8> {
+    let keep = image.len().saturating_sub(drop_bytes);
+    image.truncate(keep);
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_assemble_and_read_v6_image() -> Result<()> {
+        let mut image = SstImageBuilder::new(6, ChecksumType::XXH3).with_base_context_checksum(0xC0FFEE);
+
+        let properties =
+            image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![2])])?;
+        let metaindex = image.add_metaindex(&[("rocksdb.properties", properties)])?;
+        let bytes = image.finish(metaindex.clone(), BlockHandle::null())?;
+
+        let mut reader = Cursor::new(bytes);
+        let footer = Footer::read_from(&mut reader)?;
+        assert_eq!(footer.format_version, 6);
+        assert_eq!(footer.metaindex_handle, metaindex);
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_magic_is_rejected() -> Result<()> {
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let metaindex = image.add_block(&[])?;
+        let mut bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        corrupt_magic(&mut bytes);
+
+        let mut reader = Cursor::new(bytes);
+        let result = Footer::read_from(&mut reader);
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::InvalidMagicNumber(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_v6_reserved_field_is_rejected() -> Result<()> {
+        let mut image = SstImageBuilder::new(6, ChecksumType::CRC32c);
+        let metaindex = image.add_block(&[])?;
+        let mut bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        corrupt_v6_reserved_field(&mut bytes);
+
+        let mut reader = Cursor::new(bytes);
+        let result = Footer::read_from(&mut reader);
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::ReservedFieldNonZero { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_v5_image_keeps_base_context_checksum_zero() {
+        let builder = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        assert_eq!(builder.base_context_checksum, 0);
+    }
+
+    #[test]
+    fn test_v6_image_picks_a_random_base_context_checksum_per_file() {
+        let a = SstImageBuilder::new(6, ChecksumType::CRC32c);
+        let b = SstImageBuilder::new(6, ChecksumType::CRC32c);
+        assert_ne!(a.base_context_checksum, b.base_context_checksum);
+    }
+
+    #[test]
+    fn test_with_base_context_checksum_overrides_the_random_default() {
+        let builder = SstImageBuilder::new(6, ChecksumType::CRC32c).with_base_context_checksum(0x42);
+        assert_eq!(builder.base_context_checksum, 0x42);
+    }
+
+    #[test]
+    fn test_add_raw_block_roundtrips_through_metaindex() -> Result<()> {
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+
+        let dict = image.add_raw_block(b"shared dictionary bytes")?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![2])])?;
+        let metaindex = image.add_metaindex(&[
+            ("rocksdb.compression_dict", dict),
+            ("rocksdb.properties", properties),
+        ])?;
+        let bytes = image.finish(metaindex.clone(), BlockHandle::null())?;
+
+        let mut reader = Cursor::new(bytes);
+        let footer = Footer::read_from(&mut reader)?;
+        assert_eq!(footer.metaindex_handle, metaindex);
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncated_footer_is_rejected() -> Result<()> {
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let metaindex = image.add_block(&[])?;
+        let bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        let truncated = truncate_footer(bytes, 40);
+
+        let mut reader = Cursor::new(truncated);
+        let result = Footer::read_from(&mut reader);
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -1,28 +1,208 @@
+use crate::compressor::CompressorRegistry;
 use crate::error::{Error, Result};
 use crate::types::CompressionType;
 
-/// Decompress data according to the specified compression type
+/// Decompress data according to the specified compression type.
+///
+/// `Zstd`, `LZ4`/`LZ4HC`, `BZip2`, and `XPRESS` (backed by an LZMA codec) are
+/// gated behind the `compress-zstd`, `compress-lz4`, `compress-bzip2`, and
+/// `compress-lzma` cargo features respectively, mirroring how nod-rs gates
+/// its optional bzip2/lzma/zstd backends — callers that only need Snappy/Zlib
+/// avoid pulling in the rest of the codec stack. With `compress-lz4` on, the
+/// further `compress-lz4-flex` feature swaps LZ4 from the C `lz4` binding to the
+/// pure-Rust `lz4_flex` crate, for targets without a C toolchain; both
+/// backends produce the same wire format.
 pub fn decompress(data: &[u8], compression_type: CompressionType) -> Result<Vec<u8>> {
     match compression_type {
         CompressionType::None => Ok(data.to_vec()),
         CompressionType::Snappy => decompress_snappy(data),
         CompressionType::Zlib => decompress_zlib(data),
-        CompressionType::LZ4 => decompress_lz4(data),
+        #[cfg(feature = "compress-lz4")]
+        CompressionType::LZ4 | CompressionType::LZ4HC => decompress_lz4(data),
+        #[cfg(not(feature = "compress-lz4"))]
+        CompressionType::LZ4 | CompressionType::LZ4HC => {
+            Err(Error::UnsupportedCompressionType(compression_type as u8))
+        }
+        #[cfg(feature = "compress-bzip2")]
+        CompressionType::BZip2 => decompress_bzip2(data),
+        #[cfg(not(feature = "compress-bzip2"))]
+        CompressionType::BZip2 => Err(Error::UnsupportedCompressionType(compression_type as u8)),
+        #[cfg(feature = "compress-lzma")]
+        CompressionType::XPRESS => decompress_lzma(data),
+        #[cfg(not(feature = "compress-lzma"))]
+        CompressionType::XPRESS => Err(Error::UnsupportedCompressionType(compression_type as u8)),
+        #[cfg(feature = "compress-zstd")]
         CompressionType::ZSTD => decompress_zstd(data),
-        _ => Err(Error::UnsupportedCompressionType(compression_type as u8)),
+        #[cfg(not(feature = "compress-zstd"))]
+        CompressionType::ZSTD => Err(Error::UnsupportedCompressionType(compression_type as u8)),
     }
 }
 
-/// Compress data according to the specified compression type
+/// Like [`decompress`], but given a ZSTD dictionary, correctly decodes
+/// ZSTD blocks that were dictionary-compressed with it (see
+/// [`compress_zstd_with_dict`]) — `decompress` alone has no dictionary to
+/// resolve those blocks' back-references against and would mis-decode or
+/// fail on them. `dict` is ignored for every other compression type.
+pub fn decompress_with_dict(
+    data: &[u8],
+    compression_type: CompressionType,
+    dict: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    #[cfg(feature = "compress-zstd")]
+    if compression_type == CompressionType::ZSTD {
+        if let Some(dict) = dict {
+            return decompress_zstd_with_dict(data, dict);
+        }
+    }
+
+    decompress(data, compression_type)
+}
+
+/// Like [`decompress`], but `id` is a raw trailer compression-id byte rather
+/// than a [`CompressionType`] the caller has already validated. Ids outside
+/// the built-in range are resolved through `registry` instead of failing
+/// closed with [`Error::UnsupportedCompressionType`], so a table written by
+/// another engine with a custom codec id can still be read once that id is
+/// registered (see [`CompressorRegistry::register`]).
+pub fn decompress_by_id(data: &[u8], id: u8, registry: &CompressorRegistry) -> Result<Vec<u8>> {
+    match CompressionType::try_from(id) {
+        Ok(compression_type) => decompress(data, compression_type),
+        Err(_) => match registry.get(id) {
+            Some(compressor) => compressor.decompress(data),
+            None => Err(Error::UnsupportedCompressionType(id)),
+        },
+    }
+}
+
+/// Like [`compress`], but `id` is a raw trailer compression-id byte; see
+/// [`decompress_by_id`].
+pub fn compress_by_id(data: &[u8], id: u8, registry: &CompressorRegistry) -> Result<Vec<u8>> {
+    match CompressionType::try_from(id) {
+        Ok(compression_type) => compress(data, compression_type),
+        Err(_) => match registry.get(id) {
+            Some(compressor) => compressor.compress(data),
+            None => Err(Error::UnsupportedCompressionType(id)),
+        },
+    }
+}
+
+/// Options controlling [`compress_with_options`].
+#[derive(Debug, Clone, Copy)]
+pub struct CompressionOptions {
+    /// If the compressed output is more than this percentage of the input's
+    /// size, `compress_with_options` discards it and falls back to
+    /// `CompressionType::None` instead, since decompressing it later would
+    /// cost more than it saved on disk. Mirrors nydus' minimum-ratio
+    /// heuristic. Must be in `0..=100`; default 90.
+    pub max_compressed_ratio_percent: u8,
+    /// ZSTD compression level, `-5..=22` (more negative is faster, 22 is the
+    /// best ratio). Ignored for other compression types. Default 0, ZSTD's
+    /// own default.
+    pub zstd_level: i32,
+    /// Zlib compression level, `0..=9` (0 is stored, 9 is the best ratio).
+    /// Ignored for other compression types. Default 6, flate2's
+    /// `Compression::default()`.
+    pub zlib_level: u32,
+}
+
+impl Default for CompressionOptions {
+    fn default() -> Self {
+        CompressionOptions {
+            max_compressed_ratio_percent: 90,
+            zstd_level: 0,
+            zlib_level: 6,
+        }
+    }
+}
+
+/// Like [`compress`], but falls back to storing `data` uncompressed when the
+/// result doesn't shrink it enough per `options`, returning the compression
+/// type actually used alongside the bytes so callers (e.g. the block writer)
+/// can record the type that matches what they got back in the block
+/// trailer, rather than the type they asked for. For ZSTD and Zlib, `options`
+/// also picks the compression level; decompression stays level-agnostic, so
+/// this is purely a writer-side knob letting callers trade ratio for speed
+/// (e.g. a fast low level for hot flush paths, a high level for cold
+/// compaction output).
+pub fn compress_with_options(
+    data: &[u8],
+    compression_type: CompressionType,
+    options: CompressionOptions,
+) -> Result<(CompressionType, Vec<u8>)> {
+    if compression_type == CompressionType::None || data.is_empty() {
+        return Ok((CompressionType::None, data.to_vec()));
+    }
+
+    let compressed = compress_with_level(data, compression_type, &options)?;
+    let ratio_percent = (compressed.len() as u64 * 100) / data.len() as u64;
+
+    if ratio_percent > options.max_compressed_ratio_percent as u64 {
+        return Ok((CompressionType::None, data.to_vec()));
+    }
+
+    Ok((compression_type, compressed))
+}
+
+/// Like [`compress`], but ZSTD and Zlib use the level carried in `options`
+/// instead of their hardcoded defaults.
+fn compress_with_level(
+    data: &[u8],
+    compression_type: CompressionType,
+    options: &CompressionOptions,
+) -> Result<Vec<u8>> {
+    match compression_type {
+        CompressionType::Zlib => compress_zlib_with_level(data, options.zlib_level),
+        #[cfg(feature = "compress-zstd")]
+        CompressionType::ZSTD => compress_zstd_with_level(data, options.zstd_level),
+        other => compress(data, other),
+    }
+}
+
+/// Compress data according to the specified compression type. See
+/// [`decompress`] for the feature-gating rules that apply to each codec.
 pub fn compress(data: &[u8], compression_type: CompressionType) -> Result<Vec<u8>> {
     match compression_type {
         CompressionType::None => Ok(data.to_vec()),
         CompressionType::Snappy => compress_snappy(data),
         CompressionType::Zlib => compress_zlib(data),
-        CompressionType::LZ4 => compress_lz4(data),
+        #[cfg(feature = "compress-lz4")]
+        CompressionType::LZ4 | CompressionType::LZ4HC => compress_lz4(data),
+        #[cfg(not(feature = "compress-lz4"))]
+        CompressionType::LZ4 | CompressionType::LZ4HC => {
+            Err(Error::UnsupportedCompressionType(compression_type as u8))
+        }
+        #[cfg(feature = "compress-bzip2")]
+        CompressionType::BZip2 => compress_bzip2(data),
+        #[cfg(not(feature = "compress-bzip2"))]
+        CompressionType::BZip2 => Err(Error::UnsupportedCompressionType(compression_type as u8)),
+        #[cfg(feature = "compress-lzma")]
+        CompressionType::XPRESS => compress_lzma(data),
+        #[cfg(not(feature = "compress-lzma"))]
+        CompressionType::XPRESS => Err(Error::UnsupportedCompressionType(compression_type as u8)),
+        #[cfg(feature = "compress-zstd")]
         CompressionType::ZSTD => compress_zstd(data),
-        _ => Err(Error::UnsupportedCompressionType(compression_type as u8)),
+        #[cfg(not(feature = "compress-zstd"))]
+        CompressionType::ZSTD => Err(Error::UnsupportedCompressionType(compression_type as u8)),
+    }
+}
+
+/// Like [`compress`], but given a ZSTD dictionary, dictionary-compresses
+/// ZSTD blocks (see [`compress_zstd_with_dict`]) for better ratios on many
+/// small, similar blocks. `dict` is ignored for every other compression
+/// type.
+pub fn compress_with_dict(
+    data: &[u8],
+    compression_type: CompressionType,
+    dict: Option<&[u8]>,
+) -> Result<Vec<u8>> {
+    #[cfg(feature = "compress-zstd")]
+    if compression_type == CompressionType::ZSTD {
+        if let Some(dict) = dict {
+            return compress_zstd_with_dict(data, dict);
+        }
     }
+
+    compress(data, compression_type)
 }
 
 fn compress_snappy(data: &[u8]) -> Result<Vec<u8>> {
@@ -32,11 +212,15 @@ fn compress_snappy(data: &[u8]) -> Result<Vec<u8>> {
 }
 
 fn compress_zlib(data: &[u8]) -> Result<Vec<u8>> {
+    compress_zlib_with_level(data, flate2::Compression::default().level())
+}
+
+fn compress_zlib_with_level(data: &[u8], level: u32) -> Result<Vec<u8>> {
     use flate2::Compression;
     use flate2::write::ZlibEncoder;
     use std::io::Write;
 
-    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::new(level));
     encoder
         .write_all(data)
         .map_err(|e| Error::Decompression(format!("Zlib compression failed: {}", e)))?;
@@ -45,8 +229,13 @@ fn compress_zlib(data: &[u8]) -> Result<Vec<u8>> {
         .map_err(|e| Error::Decompression(format!("Zlib compression failed: {}", e)))
 }
 
+// RocksDB's LZ4 wire layout is a 4-byte little-endian uncompressed-size
+// header followed by a raw LZ4 block, regardless of which backend produced
+// it. The `compress-lz4-flex` feature swaps in the pure-Rust `lz4_flex` crate (no C
+// toolchain needed, so it builds for wasm/cross targets the C `lz4` binding
+// can't) while keeping that exact framing.
+#[cfg(all(feature = "compress-lz4", not(feature = "compress-lz4-flex")))]
 fn compress_lz4(data: &[u8]) -> Result<Vec<u8>> {
-    // LZ4 in RocksDB includes a 4-byte uncompressed size header
     let compressed_block = lz4::block::compress(data, None, false)
         .map_err(|e| Error::Decompression(format!("LZ4 compression failed: {}", e)))?;
 
@@ -56,11 +245,90 @@ fn compress_lz4(data: &[u8]) -> Result<Vec<u8>> {
     Ok(result)
 }
 
+#[cfg(all(feature = "compress-lz4", feature = "compress-lz4-flex"))]
+fn compress_lz4(data: &[u8]) -> Result<Vec<u8>> {
+    let compressed_block = lz4_flex::block::compress(data);
+
+    let mut result = Vec::new();
+    result.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    result.extend_from_slice(&compressed_block);
+    Ok(result)
+}
+
+#[cfg(feature = "compress-zstd")]
 fn compress_zstd(data: &[u8]) -> Result<Vec<u8>> {
-    zstd::stream::encode_all(data, 0)
+    compress_zstd_with_level(data, 0)
+}
+
+#[cfg(feature = "compress-zstd")]
+fn compress_zstd_with_level(data: &[u8], level: i32) -> Result<Vec<u8>> {
+    zstd::stream::encode_all(data, level)
         .map_err(|e| Error::Decompression(format!("ZSTD compression failed: {}", e)))
 }
 
+/// Compress `data` against a pre-trained ZSTD dictionary (see
+/// [`train_zstd_dictionary`]), for good ratios on many small, similar blocks
+/// — short keys/values in particular — where dictionary-less ZSTD does
+/// poorly since each block is compressed in isolation.
+#[cfg(feature = "compress-zstd")]
+pub fn compress_zstd_with_dict(data: &[u8], dict: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Write;
+    use zstd::dict::EncoderDictionary;
+    use zstd::stream::Encoder;
+
+    let encoder_dict = EncoderDictionary::copy(dict, 0);
+    let mut output = Vec::new();
+    let mut encoder = Encoder::with_prepared_dictionary(&mut output, &encoder_dict)
+        .map_err(|e| Error::Decompression(format!("ZSTD dictionary compression failed: {}", e)))?;
+    encoder
+        .write_all(data)
+        .map_err(|e| Error::Decompression(format!("ZSTD dictionary compression failed: {}", e)))?;
+    encoder
+        .finish()
+        .map_err(|e| Error::Decompression(format!("ZSTD dictionary compression failed: {}", e)))?;
+    Ok(output)
+}
+
+/// Train a reusable ZSTD dictionary from a set of representative sample
+/// blocks (e.g. a table's own data blocks as they're written), capped at
+/// `max_size` bytes, for use with [`compress_zstd_with_dict`] /
+/// [`decompress_zstd_with_dict`].
+#[cfg(feature = "compress-zstd")]
+pub fn train_zstd_dictionary(samples: &[Vec<u8>], max_size: usize) -> Result<Vec<u8>> {
+    zstd::dict::from_samples(samples, max_size)
+        .map_err(|e| Error::Compression(format!("ZSTD dictionary training failed: {}", e)))
+}
+
+#[cfg(feature = "compress-bzip2")]
+fn compress_bzip2(data: &[u8]) -> Result<Vec<u8>> {
+    use bzip2::Compression;
+    use bzip2::write::BzEncoder;
+    use std::io::Write;
+
+    let mut encoder = BzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .map_err(|e| Error::Decompression(format!("BZip2 compression failed: {}", e)))?;
+    encoder
+        .finish()
+        .map_err(|e| Error::Decompression(format!("BZip2 compression failed: {}", e)))
+}
+
+#[cfg(feature = "compress-lzma")]
+fn compress_lzma(data: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Write;
+
+    // RocksDB's XPRESS slot is backed here by an LZMA codec rather than
+    // Microsoft's XPRESS, since XPRESS itself has no portable Rust crate.
+    let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+    encoder
+        .write_all(data)
+        .map_err(|e| Error::Decompression(format!("LZMA compression failed: {}", e)))?;
+    encoder
+        .finish()
+        .map_err(|e| Error::Decompression(format!("LZMA compression failed: {}", e)))
+}
+
 fn decompress_snappy(data: &[u8]) -> Result<Vec<u8>> {
     snap::raw::Decoder::new()
         .decompress_vec(data)
@@ -80,6 +348,7 @@ fn decompress_zlib(data: &[u8]) -> Result<Vec<u8>> {
     Ok(decompressed)
 }
 
+#[cfg(all(feature = "compress-lz4", not(feature = "compress-lz4-flex")))]
 fn decompress_lz4(data: &[u8]) -> Result<Vec<u8>> {
     // LZ4 in RocksDB includes a 4-byte uncompressed size header
     if data.len() < 4 {
@@ -93,11 +362,73 @@ fn decompress_lz4(data: &[u8]) -> Result<Vec<u8>> {
         .map_err(|e| Error::Decompression(format!("LZ4 decompression failed: {}", e)))
 }
 
+#[cfg(all(feature = "compress-lz4", feature = "compress-lz4-flex"))]
+fn decompress_lz4(data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < 4 {
+        return Err(Error::Decompression("LZ4 data too short".to_string()));
+    }
+
+    let uncompressed_size = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let compressed_data = &data[4..];
+
+    lz4_flex::block::decompress(compressed_data, uncompressed_size)
+        .map_err(|e| Error::Decompression(format!("LZ4 decompression failed: {}", e)))
+}
+
+#[cfg(feature = "compress-zstd")]
 fn decompress_zstd(data: &[u8]) -> Result<Vec<u8>> {
     zstd::stream::decode_all(data)
         .map_err(|e| Error::Decompression(format!("ZSTD decompression failed: {}", e)))
 }
 
+/// Decompress data produced by [`compress_zstd_with_dict`] against the same
+/// dictionary. Plain [`decompress`] would otherwise silently mis-decode
+/// dictionary-compressed ZSTD blocks (or fail outright), since it never
+/// supplies the dictionary ZSTD needs to resolve back-references into it.
+#[cfg(feature = "compress-zstd")]
+pub fn decompress_zstd_with_dict(data: &[u8], dict: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Read;
+    use zstd::dict::DecoderDictionary;
+    use zstd::stream::Decoder;
+
+    let decoder_dict = DecoderDictionary::copy(dict);
+    let mut decoder = Decoder::with_prepared_dictionary(data, &decoder_dict).map_err(|e| {
+        Error::Decompression(format!("ZSTD dictionary decompression failed: {}", e))
+    })?;
+    let mut decompressed = Vec::new();
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| Error::Decompression(format!("ZSTD dictionary decompression failed: {}", e)))?;
+    Ok(decompressed)
+}
+
+#[cfg(feature = "compress-bzip2")]
+fn decompress_bzip2(data: &[u8]) -> Result<Vec<u8>> {
+    use bzip2::read::BzDecoder;
+    use std::io::Read;
+
+    let mut decoder = BzDecoder::new(data);
+    let mut decompressed = Vec::new();
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| Error::Decompression(format!("BZip2 decompression failed: {}", e)))?;
+
+    Ok(decompressed)
+}
+
+#[cfg(feature = "compress-lzma")]
+fn decompress_lzma(data: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut decoder = xz2::read::XzDecoder::new(data);
+    let mut decompressed = Vec::new();
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| Error::Decompression(format!("LZMA decompression failed: {}", e)))?;
+
+    Ok(decompressed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,6 +473,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "compress-lz4")]
     fn test_lz4_compression() -> Result<()> {
         let original = b"hello world hello world hello world";
         let compressed_block = lz4::block::compress(original, None, false)
@@ -158,6 +490,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "compress-zstd")]
     fn test_zstd_compression() -> Result<()> {
         let original = b"hello world hello world hello world";
         let compressed = zstd::stream::encode_all(&original[..], 0)
@@ -169,6 +502,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(not(feature = "compress-bzip2"))]
     fn test_unsupported_compression() -> Result<()> {
         let data = b"hello world";
         let result = decompress(data, CompressionType::BZip2);
@@ -206,6 +540,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "compress-lz4")]
     fn test_round_trip_lz4() -> Result<()> {
         let original = b"hello world hello world hello world";
         let compressed = compress(original, CompressionType::LZ4)?;
@@ -216,6 +551,24 @@ mod tests {
     }
 
     #[test]
+    #[cfg(all(feature = "compress-lz4", feature = "compress-lz4-flex"))]
+    fn test_lz4_flex_backend_matches_wire_format() -> Result<()> {
+        let original = b"hello world hello world hello world";
+        let compressed = compress(original, CompressionType::LZ4)?;
+
+        // Wire format: 4-byte LE uncompressed-size prefix + raw LZ4 block,
+        // regardless of backend.
+        let uncompressed_size = u32::from_le_bytes(compressed[..4].try_into().unwrap()) as usize;
+        assert_eq!(uncompressed_size, original.len());
+
+        let block = lz4_flex::block::decompress(&compressed[4..], uncompressed_size)
+            .map_err(|e| Error::Decompression(e.to_string()))?;
+        assert_eq!(block, original);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "compress-zstd")]
     fn test_round_trip_zstd() -> Result<()> {
         let original = b"hello world hello world hello world";
         let compressed = compress(original, CompressionType::ZSTD)?;
@@ -224,4 +577,195 @@ mod tests {
         assert!(compressed.len() < original.len());
         Ok(())
     }
+
+    #[test]
+    #[cfg(feature = "compress-bzip2")]
+    fn test_round_trip_bzip2() -> Result<()> {
+        let original = b"hello world hello world hello world";
+        let compressed = compress(original, CompressionType::BZip2)?;
+        let decompressed = decompress(&compressed, CompressionType::BZip2)?;
+        assert_eq!(decompressed, original);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "compress-bzip2")]
+    fn test_round_trip_bzip2_empty_data() -> Result<()> {
+        let original = b"";
+        let compressed = compress(original, CompressionType::BZip2)?;
+        let decompressed = decompress(&compressed, CompressionType::BZip2)?;
+        assert_eq!(decompressed, original);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "compress-lzma")]
+    fn test_round_trip_lzma() -> Result<()> {
+        let original = b"hello world hello world hello world";
+        let compressed = compress(original, CompressionType::XPRESS)?;
+        let decompressed = decompress(&compressed, CompressionType::XPRESS)?;
+        assert_eq!(decompressed, original);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_with_options_keeps_compression_when_it_shrinks() -> Result<()> {
+        let original = b"hello world hello world hello world";
+        let (used, bytes) = compress_with_options(
+            original,
+            CompressionType::Zlib,
+            CompressionOptions::default(),
+        )?;
+        assert_eq!(used, CompressionType::Zlib);
+        assert!(bytes.len() < original.len());
+        assert_eq!(decompress(&bytes, used)?, original);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_with_options_falls_back_when_ratio_is_poor() -> Result<()> {
+        // A single byte: Zlib's own framing overhead makes the "compressed"
+        // output larger than the input, well past the default 90% threshold.
+        let original = b"a";
+        let (used, bytes) = compress_with_options(
+            original,
+            CompressionType::Zlib,
+            CompressionOptions::default(),
+        )?;
+        assert_eq!(used, CompressionType::None);
+        assert_eq!(bytes, original);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_with_options_none_type_is_passthrough() -> Result<()> {
+        let original = b"hello world";
+        let (used, bytes) =
+            compress_with_options(original, CompressionType::None, CompressionOptions::default())?;
+        assert_eq!(used, CompressionType::None);
+        assert_eq!(bytes, original);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_with_options_honors_zlib_level() -> Result<()> {
+        let original = b"hello world hello world hello world hello world hello world";
+        let fast = compress_with_options(
+            original,
+            CompressionType::Zlib,
+            CompressionOptions {
+                zlib_level: 0,
+                ..CompressionOptions::default()
+            },
+        )?;
+        let best = compress_with_options(
+            original,
+            CompressionType::Zlib,
+            CompressionOptions {
+                zlib_level: 9,
+                ..CompressionOptions::default()
+            },
+        )?;
+
+        assert_eq!(decompress(&fast.1, fast.0)?, original);
+        assert_eq!(decompress(&best.1, best.0)?, original);
+        assert!(best.1.len() <= fast.1.len());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "compress-zstd")]
+    fn test_compress_with_options_honors_zstd_level() -> Result<()> {
+        let original = b"hello world hello world hello world hello world hello world";
+        let (used, bytes) = compress_with_options(
+            original,
+            CompressionType::ZSTD,
+            CompressionOptions {
+                zstd_level: 19,
+                ..CompressionOptions::default()
+            },
+        )?;
+        assert_eq!(used, CompressionType::ZSTD);
+        assert_eq!(decompress(&bytes, used)?, original);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "compress-zstd")]
+    fn test_zstd_dictionary_round_trip() -> Result<()> {
+        let samples: Vec<Vec<u8>> = (0..64)
+            .map(|i| format!("key{:04}:value-for-row-{:04}", i, i).into_bytes())
+            .collect();
+        let dict = train_zstd_dictionary(&samples, 4096)?;
+
+        let original = b"key9999:value-for-row-9999";
+        let compressed = compress_zstd_with_dict(original, &dict)?;
+        let decompressed = decompress_zstd_with_dict(&compressed, &dict)?;
+        assert_eq!(decompressed, original);
+
+        // compress_with_dict / decompress_with_dict dispatch to the same
+        // dictionary-aware codepath for CompressionType::ZSTD.
+        let compressed = compress_with_dict(original, CompressionType::ZSTD, Some(&dict))?;
+        let decompressed = decompress_with_dict(&compressed, CompressionType::ZSTD, Some(&dict))?;
+        assert_eq!(decompressed, original);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_with_dict_ignores_dict_for_other_types() -> Result<()> {
+        let original = b"hello world hello world hello world";
+        let dict = b"irrelevant";
+        let compressed = compress_with_dict(original, CompressionType::Zlib, Some(dict))?;
+        let decompressed = decompress_with_dict(&compressed, CompressionType::Zlib, Some(dict))?;
+        assert_eq!(decompressed, original);
+        Ok(())
+    }
+
+    struct ReverseBytes;
+
+    impl crate::compressor::Compressor for ReverseBytes {
+        fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+            let mut reversed = data.to_vec();
+            reversed.reverse();
+            Ok(reversed)
+        }
+
+        fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+            self.compress(data)
+        }
+    }
+
+    #[test]
+    fn test_compress_by_id_and_decompress_by_id_use_built_ins_for_known_ids() -> Result<()> {
+        let registry = CompressorRegistry::new();
+        let original = b"hello world hello world hello world";
+
+        let compressed = compress_by_id(original, CompressionType::Zlib as u8, &registry)?;
+        let decompressed = decompress_by_id(&compressed, CompressionType::Zlib as u8, &registry)?;
+        assert_eq!(decompressed, original);
+        assert!(compressed.len() < original.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compress_by_id_and_decompress_by_id_use_the_registry_for_custom_ids() -> Result<()> {
+        let mut registry = CompressorRegistry::new();
+        registry.register(200, ReverseBytes);
+        let original = b"hello world";
+
+        let compressed = compress_by_id(original, 200, &registry)?;
+        assert_eq!(compressed, original.iter().rev().copied().collect::<Vec<u8>>());
+
+        let decompressed = decompress_by_id(&compressed, 200, &registry)?;
+        assert_eq!(decompressed, original);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decompress_by_id_fails_for_an_unregistered_custom_id() {
+        let registry = CompressorRegistry::new();
+        let result = decompress_by_id(b"anything", 200, &registry);
+        assert!(matches!(result, Err(Error::UnsupportedCompressionType(200))));
+    }
 }
@@ -0,0 +1,110 @@
+// Copyright 2024 YaleDB Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A pluggable registry layered on top of [`crate::types::CompressionType`],
+//! for reading (and writing) block trailer compression ids that crate's
+//! closed, built-in enum doesn't know about — e.g. a legacy codec used by
+//! another engine's SST files. [`crate::compression::compress_by_id`] and
+//! [`crate::compression::decompress_by_id`] consult a [`CompressorRegistry`]
+//! for any id outside the built-in range, so the one-byte compression id is
+//! an extensible namespace rather than a fixed set of variants.
+
+use crate::error::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A codec for a single custom compression id, registered with a
+/// [`CompressorRegistry`]. Implementations are responsible for their own
+/// wire framing (e.g. a length prefix), the same way the built-in codecs in
+/// [`crate::compression`] are.
+pub trait Compressor: Send + Sync {
+    fn compress(&self, data: &[u8]) -> Result<Vec<u8>>;
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Maps compression ids outside the built-in [`crate::types::CompressionType`]
+/// range to a caller-supplied [`Compressor`]. Empty by default; callers
+/// register foreign ids explicitly via [`Self::register`].
+#[derive(Clone, Default)]
+pub struct CompressorRegistry {
+    custom: HashMap<u8, Arc<dyn Compressor>>,
+}
+
+impl CompressorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `compressor` for `id`, replacing any codec previously
+    /// registered for it.
+    pub fn register(&mut self, id: u8, compressor: impl Compressor + 'static) {
+        self.custom.insert(id, Arc::new(compressor));
+    }
+
+    /// The codec registered for `id`, if any.
+    pub fn get(&self, id: u8) -> Option<&Arc<dyn Compressor>> {
+        self.custom.get(&id)
+    }
+}
+
+impl std::fmt::Debug for CompressorRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut ids: Vec<&u8> = self.custom.keys().collect();
+        ids.sort();
+        f.debug_struct("CompressorRegistry")
+            .field("registered_ids", &ids)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Xor(u8);
+
+    impl Compressor for Xor {
+        fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(data.iter().map(|b| b ^ self.0).collect())
+        }
+
+        fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(data.iter().map(|b| b ^ self.0).collect())
+        }
+    }
+
+    #[test]
+    fn test_register_then_get_returns_the_same_codec() {
+        let mut registry = CompressorRegistry::new();
+        assert!(registry.get(200).is_none());
+
+        registry.register(200, Xor(0x42));
+        let compressor = registry.get(200).expect("should be registered");
+
+        let original = b"hello world";
+        let compressed = compressor.compress(original).unwrap();
+        assert_ne!(compressed, original);
+        assert_eq!(compressor.decompress(&compressed).unwrap(), original);
+    }
+
+    #[test]
+    fn test_register_overwrites_a_previous_codec_for_the_same_id() {
+        let mut registry = CompressorRegistry::new();
+        registry.register(200, Xor(0x01));
+        registry.register(200, Xor(0x02));
+
+        let compressed = registry.get(200).unwrap().compress(b"abc").unwrap();
+        assert_eq!(compressed, Xor(0x02).compress(b"abc").unwrap());
+    }
+
+    #[test]
+    fn test_debug_reports_registered_ids_without_requiring_compressor_debug() {
+        let mut registry = CompressorRegistry::new();
+        registry.register(200, Xor(0x42));
+        registry.register(9, Xor(0x01));
+
+        let formatted = format!("{:?}", registry);
+        assert!(formatted.contains("9"));
+        assert!(formatted.contains("200"));
+    }
+}
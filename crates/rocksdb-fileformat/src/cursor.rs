@@ -0,0 +1,173 @@
+// Copyright 2024 YaleDB Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A single, testable primitive for checked little-endian and varint
+//! decoding, implemented for both a forward `Cursor<&[u8]>` and
+//! [`crate::footer::ReverseCursor`], so parsers across the crate share one
+//! set of bounds checks instead of each re-rolling their own.
+
+use crate::error::{Error, Result};
+use byteorder::{ByteOrder, LittleEndian};
+use std::io::{Cursor, Read};
+
+/// Checked binary-cursor accessors. Every checked method reports
+/// [`BinCursor::offset`] when it runs out of input (an over-long varint
+/// fails with [`Error::InvalidVarint`], which carries no offset); every
+/// `try_` method instead returns
+/// `None` on short input, for callers that just want to probe ahead.
+pub trait BinCursor {
+    /// The cursor's current position, in whatever coordinate space the
+    /// implementor tracks — a byte offset into a buffer for `Cursor<&[u8]>`,
+    /// or an absolute file offset for [`crate::footer::ReverseCursor`].
+    fn offset(&self) -> u64;
+
+    /// Bytes available to read without error.
+    fn remaining(&self) -> usize;
+
+    /// Read exactly `buf.len()` bytes, or fail with
+    /// [`Error::TruncatedField`] naming `field` and the current offset.
+    fn read_bytes(&mut self, buf: &mut [u8], field: &'static str) -> Result<()>;
+
+    /// Read a little-endian varint64, or fail with [`Error::InvalidVarint`]
+    /// (too many bytes) or [`Error::TruncatedField`] (ran out of input).
+    fn read_varint64(&mut self, field: &'static str) -> Result<u64>;
+
+    fn read_u8(&mut self, field: &'static str) -> Result<u8> {
+        let mut buf = [0u8; 1];
+        self.read_bytes(&mut buf, field)?;
+        Ok(buf[0])
+    }
+
+    fn read_u32(&mut self, field: &'static str) -> Result<u32> {
+        let mut buf = [0u8; 4];
+        self.read_bytes(&mut buf, field)?;
+        Ok(LittleEndian::read_u32(&buf))
+    }
+
+    fn read_i32(&mut self, field: &'static str) -> Result<i32> {
+        let mut buf = [0u8; 4];
+        self.read_bytes(&mut buf, field)?;
+        Ok(LittleEndian::read_i32(&buf))
+    }
+
+    fn read_u64(&mut self, field: &'static str) -> Result<u64> {
+        let mut buf = [0u8; 8];
+        self.read_bytes(&mut buf, field)?;
+        Ok(LittleEndian::read_u64(&buf))
+    }
+
+    fn try_u8(&mut self) -> Option<u8> {
+        self.read_u8("").ok()
+    }
+
+    fn try_u32(&mut self) -> Option<u32> {
+        self.read_u32("").ok()
+    }
+
+    fn try_i32(&mut self) -> Option<i32> {
+        self.read_i32("").ok()
+    }
+
+    fn try_u64(&mut self) -> Option<u64> {
+        self.read_u64("").ok()
+    }
+
+    fn try_varint64(&mut self) -> Option<u64> {
+        self.read_varint64("").ok()
+    }
+}
+
+impl<'a> BinCursor for Cursor<&'a [u8]> {
+    fn offset(&self) -> u64 {
+        self.position()
+    }
+
+    fn remaining(&self) -> usize {
+        let pos = self.position() as usize;
+        self.get_ref().len().saturating_sub(pos)
+    }
+
+    fn read_bytes(&mut self, buf: &mut [u8], field: &'static str) -> Result<()> {
+        let have = self.remaining();
+        if have < buf.len() {
+            return Err(Error::TruncatedField {
+                offset: self.offset(),
+                field,
+                need: buf.len(),
+                have,
+            });
+        }
+
+        self.read_exact(buf)?;
+        Ok(())
+    }
+
+    fn read_varint64(&mut self, field: &'static str) -> Result<u64> {
+        let mut result = 0u64;
+        let mut shift = 0;
+
+        loop {
+            if shift >= 64 {
+                return Err(Error::InvalidVarint);
+            }
+
+            let byte = self.read_u8(field)?;
+            result |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_varint64(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+        out
+    }
+
+    #[test]
+    fn test_forward_cursor_checked_reads() -> Result<()> {
+        let data = [0x01, 0x02, 0x03, 0x04, 0xAA];
+        let mut cursor = Cursor::new(&data[..]);
+
+        assert_eq!(cursor.read_u32("word")?, 0x04030201);
+        assert_eq!(cursor.offset(), 4);
+        assert_eq!(cursor.read_u8("tail")?, 0xAA);
+
+        let err = cursor.read_u8("past end").unwrap_err();
+        assert!(matches!(err, Error::TruncatedField { field: "past end", .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_forward_cursor_try_variants_on_short_input() {
+        let data = [0x01, 0x02];
+        let mut cursor = Cursor::new(&data[..]);
+
+        assert_eq!(cursor.try_u8(), Some(0x01));
+        assert_eq!(cursor.try_u32(), None);
+        // A failed checked read must not have moved the cursor.
+        assert_eq!(cursor.try_u8(), Some(0x02));
+        assert_eq!(cursor.try_u8(), None);
+    }
+
+    #[test]
+    fn test_forward_cursor_varint64_roundtrip() -> Result<()> {
+        for value in [0u64, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            let encoded = encode_varint64(value);
+            let mut cursor = Cursor::new(encoded.as_slice());
+            assert_eq!(cursor.read_varint64("value")?, value);
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,12 @@
-use crate::compression::decompress;
+use crate::block_builder::{bitunpack_lsb, zigzag_decode, COMPACT_RESTARTS_FLAG};
+use crate::block_handle::BlockHandle;
+use crate::block_trailer;
+use crate::compression::{decompress, decompress_by_id, decompress_with_dict};
+use crate::compressor::CompressorRegistry;
 use crate::error::{Error, Result};
-use crate::types::CompressionType;
-use byteorder::{LittleEndian, ReadBytesExt};
+use crate::footer::Footer;
+use crate::types::{ChecksumType, CompressionType};
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
 use std::io::Cursor;
 
 pub struct DataBlock {
@@ -11,15 +16,107 @@ pub struct DataBlock {
     restart_points: Vec<u32>,
 }
 
+#[derive(Debug, PartialEq)]
 pub struct KeyValue {
     pub key: Vec<u8>,
     pub value: Vec<u8>,
 }
 
 impl DataBlock {
+    /// Decode a block whose trailer's compression type is already known to
+    /// the caller, without checking its checksum. Prefer
+    /// [`DataBlock::new_with_checksum`] when the raw, still-trailered block bytes are available.
     pub fn new(compressed_data: &[u8], compression_type: CompressionType) -> Result<Self> {
-        let raw_data = decompress(compressed_data, compression_type)?;
+        Self::decode(compressed_data, compression_type, None, None)
+    }
+
+    /// Decode a block, optionally verifying its 5-byte trailer checksum
+    /// first. Verification recomputes the checksum over the trailer-stripped
+    /// payload plus the compression-type byte and compares it against the
+    /// stored value — for `ChecksumType::CRC32c` this means unmasking is
+    /// implicit in [`ChecksumType::calculate`] already producing the masked
+    /// form, so the two masked values are compared directly — failing with
+    /// [`Error::ChecksumMismatch`] before the block is decompressed, so
+    /// corrupted compressed data is rejected early rather than fed to a
+    /// decompressor. This reuses [`block_trailer::verify_block`] with a
+    /// synthetic pre-v6 footer, since `DataBlock` has no file offset to bind
+    /// a context checksum to; callers that already verify at the file level
+    /// (like [`crate::sst_reader::SstReader`]) should keep passing `None`
+    /// here to avoid a redundant, context-blind second check.
+    pub fn new_with_checksum(
+        compressed_data: &[u8],
+        compression_type: CompressionType,
+        checksum_type: Option<ChecksumType>,
+    ) -> Result<Self> {
+        Self::decode(compressed_data, compression_type, checksum_type, None)
+    }
+
+    /// Decode a block that may have been compressed against a shared
+    /// dictionary (e.g. `ZSTD` with a table-wide compression dictionary
+    /// loaded from the `rocksdb.compression_dict` meta block). `dict` is
+    /// ignored for compression types that don't support dictionaries.
+    /// Like [`DataBlock::new`], this does not verify the trailer checksum.
+    pub fn new_with_dict(
+        compressed_data: &[u8],
+        compression_type: CompressionType,
+        dict: Option<&[u8]>,
+    ) -> Result<Self> {
+        Self::decode(compressed_data, compression_type, None, dict)
+    }
+
+    /// Decode a block whose trailer's compression id isn't necessarily one
+    /// of the built-in [`CompressionType`] variants, resolving ids outside
+    /// that range through `registry` instead of failing closed — e.g. a
+    /// foreign engine's custom codec id (see
+    /// [`crate::compression::decompress_by_id`]). `dict` is used the same
+    /// way [`DataBlock::new_with_dict`] uses it, and is ignored for ids
+    /// `registry` resolves. Like [`DataBlock::new`], this does not verify
+    /// the trailer checksum.
+    pub fn new_with_dict_and_registry(
+        compressed_data: &[u8],
+        id: u8,
+        dict: Option<&[u8]>,
+        registry: &CompressorRegistry,
+    ) -> Result<Self> {
+        let raw_data = match (dict, CompressionType::try_from(id)) {
+            (Some(dict), Ok(compression_type)) => {
+                decompress_with_dict(compressed_data, compression_type, Some(dict))?
+            }
+            _ => decompress_by_id(compressed_data, id, registry)?,
+        };
+
+        Self::from_decompressed(raw_data)
+    }
+
+    fn decode(
+        compressed_data: &[u8],
+        compression_type: CompressionType,
+        checksum_type: Option<ChecksumType>,
+        dict: Option<&[u8]>,
+    ) -> Result<Self> {
+        if let Some(checksum_type) = checksum_type {
+            let footer = Footer {
+                checksum_type,
+                metaindex_handle: BlockHandle::new(0, 0),
+                index_handle: BlockHandle::new(0, 0),
+                format_version: 5,
+                base_context_checksum: None,
+            };
+            block_trailer::verify_block(compressed_data, &footer, 0)?;
+        }
+
+        let raw_data = match dict {
+            Some(dict) => decompress_with_dict(compressed_data, compression_type, Some(dict))?,
+            None => decompress(compressed_data, compression_type)?,
+        };
 
+        Self::from_decompressed(raw_data)
+    }
+
+    /// Parse the restart-pointed key/value format out of already-decompressed
+    /// block bytes, shared by [`Self::decode`] and
+    /// [`Self::new_with_dict_and_registry`].
+    fn from_decompressed(raw_data: Vec<u8>) -> Result<Self> {
         // RocksDB blocks have a 5-byte trailer: compression_type (1) + checksum (4)
         let data = if raw_data.len() >= 5 {
             raw_data[..raw_data.len() - 5].to_vec()
@@ -35,32 +132,40 @@ impl DataBlock {
 
         let mut cursor = Cursor::new(&data);
         cursor.set_position((data.len() - 4) as u64);
-        let num_restarts = cursor.read_u32::<LittleEndian>()?;
+        let restart_count_field = cursor.read_u32::<LittleEndian>()?;
+        let num_restarts = restart_count_field & !COMPACT_RESTARTS_FLAG;
 
         if num_restarts == 0 {
             return Err(Error::InvalidBlockFormat("No restart points".to_string()));
         }
 
-        if data.len() < 4 + (num_restarts as usize * 4) {
-            return Err(Error::InvalidBlockFormat(
-                "Data block too small to contain restart points".to_string(),
-            ));
-        }
+        let (restart_offset, restart_points) = if restart_count_field & COMPACT_RESTARTS_FLAG != 0
+        {
+            Self::decode_compact_restarts(&data, num_restarts)?
+        } else {
+            if data.len() < 4 + (num_restarts as usize * 4) {
+                return Err(Error::InvalidBlockFormat(
+                    "Data block too small to contain restart points".to_string(),
+                ));
+            }
+
+            let restart_offset = data.len() - 4 - (num_restarts as usize * 4);
+            let mut restart_points = Vec::with_capacity(num_restarts as usize);
+            let mut cursor = Cursor::new(&data);
+            cursor.set_position(restart_offset as u64);
+            for _ in 0..num_restarts {
+                restart_points.push(cursor.read_u32::<LittleEndian>()?);
+            }
+
+            (restart_offset, restart_points)
+        };
 
-        let restart_offset = data.len() - 4 - (num_restarts as usize * 4);
         if restart_offset >= data.len() {
             return Err(Error::InvalidBlockFormat(
                 "Invalid restart offset".to_string(),
             ));
         }
 
-        let mut restart_points = Vec::with_capacity(num_restarts as usize);
-        cursor.set_position(restart_offset as u64);
-
-        for _ in 0..num_restarts {
-            restart_points.push(cursor.read_u32::<LittleEndian>()?);
-        }
-
         Ok(DataBlock {
             data,
             restart_offset,
@@ -69,8 +174,77 @@ impl DataBlock {
         })
     }
 
+    /// Decode a [`crate::block_builder::DataBlockBuilder::write_restarts`]
+    /// delta-and-bitpacked restart array: `[base_u32][bitpacked
+    /// deltas][bit_width_u8][count_u32]`, read from the end inward since
+    /// `bitpacked deltas` has no fixed length of its own.
+    fn decode_compact_restarts(data: &[u8], num_restarts: u32) -> Result<(usize, Vec<u32>)> {
+        if data.len() < 5 {
+            return Err(Error::InvalidBlockFormat(
+                "Data block too small to contain a compact restart array".to_string(),
+            ));
+        }
+
+        let bit_width = data[data.len() - 5];
+        let mut num_deltas = (num_restarts as usize).saturating_sub(1);
+
+        // A corrupted restart-count field (e.g. a flipped high bit that
+        // happens to also be `COMPACT_RESTARTS_FLAG`) can claim far more
+        // deltas than `bit_width` could ever pack into the remaining
+        // buffer, or pair a zero `bit_width` with a nonzero delta count --
+        // real encodes never do that, since `write_restarts` floors
+        // `bit_width` at 1 whenever there are deltas to pack (see
+        // `bits_required`, which never returns 0). Rather than hand an
+        // attacker/corruption-controlled `num_deltas` straight to
+        // `bitunpack_lsb`'s `Vec::with_capacity` (an allocation sized
+        // before a single byte is read), fall back to treating the block
+        // as holding just its base restart point with no deltas -- the
+        // same degrade-instead-of-fail-closed move `IndexBlock::with_format`
+        // makes for its own implausible restart count.
+        if num_deltas > 0 && (bit_width == 0 || num_deltas > data.len() * 8 / bit_width as usize) {
+            num_deltas = 0;
+        }
+
+        let packed_len = (num_deltas * bit_width as usize + 7) / 8;
+
+        if data.len() < 5 + packed_len + 4 {
+            return Err(Error::InvalidBlockFormat(
+                "Data block too small to contain its compact restart array".to_string(),
+            ));
+        }
+
+        let packed_start = data.len() - 5 - packed_len;
+        let restart_offset = packed_start - 4;
+
+        let base = LittleEndian::read_u32(&data[restart_offset..packed_start]);
+        let deltas = bitunpack_lsb(&data[packed_start..packed_start + packed_len], num_deltas, bit_width);
+
+        // Sized off the (possibly clamped-down) `num_deltas` above, not the
+        // raw `num_restarts` the caller passed in, so a fallback doesn't
+        // defeat the point of taking it by still over-allocating here.
+        let mut restart_points = Vec::with_capacity(num_deltas + 1);
+        restart_points.push(base);
+        let mut last = base;
+        for delta in deltas {
+            last = (last as i64 + zigzag_decode(delta)) as u32;
+            restart_points.push(last);
+        }
+
+        Ok((restart_offset, restart_points))
+    }
+
     pub fn get_entries(&self) -> Result<Vec<KeyValue>> {
+        Ok(self.decode_entries()?.0)
+    }
+
+    /// Decode every entry, same as [`DataBlock::get_entries`], additionally
+    /// returning the entry index each restart point in
+    /// [`DataBlock::get_restart_points`] decodes to, in the same order —
+    /// used by [`DataBlockReader::seek`] to binary-search restart points
+    /// without re-scanning the raw block bytes.
+    pub(crate) fn decode_entries(&self) -> Result<(Vec<KeyValue>, Vec<usize>)> {
         let mut entries = Vec::new();
+        let mut restart_entry_indices = Vec::new();
         let mut cursor = Cursor::new(&self.data);
         let mut last_key = Vec::new();
 
@@ -80,12 +254,16 @@ impl DataBlock {
             // Check if this is a restart point BEFORE processing
             // At restart points, we should have no shared prefix
             if self.is_restart_point(entry_start as u32) {
+                restart_entry_indices.push(entries.len());
                 last_key.clear();
             }
 
             let shared_key_len = self.read_varint(&mut cursor)?;
             let unshared_key_len = self.read_varint(&mut cursor)?;
-            let value_len = self.read_varint(&mut cursor)?;
+            // varint64, not varint32 — matches how `DataBlockBuilder::add`
+            // encodes a value's length, so a value over 4 GiB still decodes
+            // correctly instead of being truncated.
+            let value_len = self.read_varint64(&mut cursor)?;
 
             if shared_key_len > last_key.len() as u32 {
                 return Err(Error::InvalidBlockFormat(
@@ -123,7 +301,7 @@ impl DataBlock {
             entries.push(KeyValue { key, value });
         }
 
-        Ok(entries)
+        Ok((entries, restart_entry_indices))
     }
 
     fn read_varint(&self, cursor: &mut Cursor<&Vec<u8>>) -> Result<u32> {
@@ -153,6 +331,33 @@ impl DataBlock {
         Ok(result)
     }
 
+    fn read_varint64(&self, cursor: &mut Cursor<&Vec<u8>>) -> Result<u64> {
+        let mut result = 0u64;
+        let mut shift = 0;
+
+        loop {
+            if (cursor.position() as usize) >= self.data.len() {
+                return Err(Error::InvalidVarint);
+            }
+
+            let byte = self.data[cursor.position() as usize];
+            cursor.set_position(cursor.position() + 1);
+
+            result |= ((byte & 0x7F) as u64) << shift;
+
+            if (byte & 0x80) == 0 {
+                break;
+            }
+
+            shift += 7;
+            if shift >= 64 {
+                return Err(Error::InvalidVarint);
+            }
+        }
+
+        Ok(result)
+    }
+
     fn is_restart_point(&self, offset: u32) -> bool {
         self.restart_points.contains(&offset)
     }
@@ -173,17 +378,70 @@ pub struct DataBlockReader {
     block: DataBlock,
     current_entry: usize,
     entries: Vec<KeyValue>,
+    /// `restart_entry_indices[i]` is the entry index `block.get_restart_points()[i]`
+    /// decodes to, kept in lockstep so [`DataBlockReader::seek`] can
+    /// binary-search restart points instead of scanning every entry.
+    restart_entry_indices: Vec<usize>,
 }
 
 impl DataBlockReader {
     pub fn new(compressed_data: &[u8], compression_type: CompressionType) -> Result<Self> {
-        let block = DataBlock::new(compressed_data, compression_type)?;
-        let entries = block.get_entries()?;
+        Self::from_block(DataBlock::new(compressed_data, compression_type)?)
+    }
+
+    /// Like [`DataBlockReader::new`], but verifies the block's trailer
+    /// checksum first. See [`DataBlock::new_with_checksum`].
+    pub fn new_with_checksum(
+        compressed_data: &[u8],
+        compression_type: CompressionType,
+        checksum_type: Option<ChecksumType>,
+    ) -> Result<Self> {
+        Self::from_block(DataBlock::new_with_checksum(
+            compressed_data,
+            compression_type,
+            checksum_type,
+        )?)
+    }
+
+    /// Like [`DataBlockReader::new`], but decompresses against a shared
+    /// dictionary. See [`DataBlock::new_with_dict`].
+    pub fn new_with_dict(
+        compressed_data: &[u8],
+        compression_type: CompressionType,
+        dict: Option<&[u8]>,
+    ) -> Result<Self> {
+        Self::from_block(DataBlock::new_with_dict(
+            compressed_data,
+            compression_type,
+            dict,
+        )?)
+    }
+
+    /// Like [`DataBlockReader::new`], but resolves `id` through `registry`
+    /// when it's outside the built-in [`CompressionType`] range. See
+    /// [`DataBlock::new_with_dict_and_registry`].
+    pub fn new_with_dict_and_registry(
+        compressed_data: &[u8],
+        id: u8,
+        dict: Option<&[u8]>,
+        registry: &CompressorRegistry,
+    ) -> Result<Self> {
+        Self::from_block(DataBlock::new_with_dict_and_registry(
+            compressed_data,
+            id,
+            dict,
+            registry,
+        )?)
+    }
+
+    fn from_block(block: DataBlock) -> Result<Self> {
+        let (entries, restart_entry_indices) = block.decode_entries()?;
 
         Ok(DataBlockReader {
             block,
             current_entry: 0,
             entries,
+            restart_entry_indices,
         })
     }
 
@@ -191,6 +449,13 @@ impl DataBlockReader {
         self.current_entry = 0;
     }
 
+    /// Position on the last entry, or leave the cursor invalid if the block
+    /// is empty. O(1) — unlike driving to the end with repeated [`Self::next`]
+    /// calls, this doesn't touch every entry.
+    pub fn seek_to_last(&mut self) {
+        self.current_entry = self.entries.len().saturating_sub(1);
+    }
+
     pub fn next(&mut self) -> Option<&KeyValue> {
         if self.current_entry < self.entries.len() {
             let entry = &self.entries[self.current_entry];
@@ -201,37 +466,125 @@ impl DataBlockReader {
         }
     }
 
+    /// Step backward onto the entry before the current one. O(1), since the
+    /// block's entries are already fully decoded in `self.entries`.
+    pub fn prev(&mut self) -> Option<&KeyValue> {
+        if self.current_entry == 0 {
+            // Walked off the front — invalidate the cursor the same way
+            // `next()` does at the back, rather than leaving it pointing at
+            // entry 0 forever.
+            self.current_entry = self.entries.len();
+            None
+        } else {
+            self.current_entry -= 1;
+            Some(&self.entries[self.current_entry])
+        }
+    }
+
     pub fn valid(&self) -> bool {
         self.current_entry < self.entries.len()
     }
 
     pub fn key(&self) -> Option<&[u8]> {
-        if self.current_entry > 0 && self.current_entry <= self.entries.len() {
-            Some(&self.entries[self.current_entry - 1].key)
+        if self.current_entry < self.entries.len() {
+            Some(&self.entries[self.current_entry].key)
         } else {
             None
         }
     }
 
     pub fn value(&self) -> Option<&[u8]> {
-        if self.current_entry > 0 && self.current_entry <= self.entries.len() {
-            Some(&self.entries[self.current_entry - 1].value)
+        if self.current_entry < self.entries.len() {
+            Some(&self.entries[self.current_entry].value)
         } else {
             None
         }
     }
 
+    /// Find the first entry whose key is `>= target_key`, mirroring
+    /// RocksDB: binary-search `restart_entry_indices` for the last restart
+    /// whose key is `< target_key`, then linear-scan only the entries in
+    /// that restart interval. O(log R + restart_interval) instead of O(n).
     pub fn seek(&mut self, target_key: &[u8]) -> bool {
-        for (i, entry) in self.entries.iter().enumerate() {
-            if entry.key.as_slice() >= target_key {
+        if self.restart_entry_indices.is_empty() {
+            self.current_entry = self.entries.len();
+            return false;
+        }
+
+        let mut left = 0usize;
+        let mut right = self.restart_entry_indices.len() - 1;
+        while left < right {
+            let mid = left + (right - left + 1) / 2;
+            let mid_entry = self.restart_entry_indices[mid];
+            if self.entries[mid_entry].key.as_slice() < target_key {
+                left = mid;
+            } else {
+                right = mid - 1;
+            }
+        }
+
+        let start = self.restart_entry_indices[left];
+        for i in start..self.entries.len() {
+            if self.entries[i].key.as_slice() >= target_key {
                 self.current_entry = i;
                 return true;
             }
         }
+
         self.current_entry = self.entries.len();
         false
     }
 
+    /// Find the last entry whose key is `<= target_key` — the backward
+    /// counterpart to [`Self::seek`]. Same restart-point binary search,
+    /// narrowed to the last restart interval that could hold such an
+    /// entry, then a linear scan of just that interval.
+    pub fn seek_for_prev(&mut self, target_key: &[u8]) -> bool {
+        if self.restart_entry_indices.is_empty() {
+            self.current_entry = self.entries.len();
+            return false;
+        }
+
+        let mut left = 0usize;
+        let mut right = self.restart_entry_indices.len() - 1;
+        while left < right {
+            let mid = left + (right - left + 1) / 2;
+            let mid_entry = self.restart_entry_indices[mid];
+            if self.entries[mid_entry].key.as_slice() <= target_key {
+                left = mid;
+            } else {
+                right = mid - 1;
+            }
+        }
+
+        let start = self.restart_entry_indices[left];
+        let end = self
+            .restart_entry_indices
+            .get(left + 1)
+            .copied()
+            .unwrap_or(self.entries.len());
+
+        let mut found = None;
+        for i in start..end {
+            if self.entries[i].key.as_slice() <= target_key {
+                found = Some(i);
+            } else {
+                break;
+            }
+        }
+
+        match found {
+            Some(i) => {
+                self.current_entry = i;
+                true
+            }
+            None => {
+                self.current_entry = self.entries.len();
+                false
+            }
+        }
+    }
+
     pub fn entries(&self) -> &[KeyValue] {
         &self.entries
     }
@@ -241,6 +594,8 @@ impl DataBlockReader {
 mod tests {
     use super::*;
     use crate::block_builder::{DataBlockBuilder, DataBlockBuilderOptions};
+    use crate::block_trailer::BLOCK_TRAILER_SIZE;
+    use crate::compressor::Compressor;
     use crate::types::CompressionType;
 
     #[test]
@@ -262,7 +617,8 @@ mod tests {
             builder.add(key, value);
         }
 
-        let block_bytes = builder.finish(CompressionType::None)?;
+        let block_bytes =
+            builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
 
         // Read the block back
         let block = DataBlock::new(&block_bytes, CompressionType::None)?;
@@ -295,7 +651,8 @@ mod tests {
             builder.add(key, value);
         }
 
-        let block_bytes = builder.finish(CompressionType::None)?;
+        let block_bytes =
+            builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
 
         // Use DataBlockReader to read back
         let mut reader = DataBlockReader::new(&block_bytes, CompressionType::None)?;
@@ -337,7 +694,8 @@ mod tests {
             builder.add(key, value);
         }
 
-        let block_bytes = builder.finish(CompressionType::None)?;
+        let block_bytes =
+            builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
 
         // Read back and verify
         let block = DataBlock::new(&block_bytes, CompressionType::None)?;
@@ -359,4 +717,356 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_data_block_roundtrip_with_compact_restarts() -> Result<()> {
+        let mut builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(2)
+                .with_compact_restarts(true),
+        );
+
+        let test_data = vec![
+            (b"a".to_vec(), b"1".to_vec()),
+            (b"b".to_vec(), b"2".to_vec()),
+            (b"c".to_vec(), b"3".to_vec()),
+            (b"d".to_vec(), b"4".to_vec()),
+            (b"e".to_vec(), b"5".to_vec()),
+            (b"f".to_vec(), b"6".to_vec()),
+        ];
+
+        for (key, value) in &test_data {
+            builder.add(key, value);
+        }
+
+        let block_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let block = DataBlock::new(&block_bytes, CompressionType::None)?;
+        let entries = block.get_entries()?;
+
+        assert_eq!(entries.len(), test_data.len());
+        for (i, entry) in entries.iter().enumerate() {
+            assert_eq!(entry.key, test_data[i].0);
+            assert_eq!(entry.value, test_data[i].1);
+        }
+
+        let restart_points = block.get_restart_points();
+        assert!(restart_points.len() >= 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compact_restarts_are_smaller_than_plain_for_many_small_restart_intervals() -> Result<()>
+    {
+        let test_data: Vec<(Vec<u8>, Vec<u8>)> = (0..64)
+            .map(|i| (format!("key{:04}", i).into_bytes(), b"v".to_vec()))
+            .collect();
+
+        let mut plain_builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default().with_restart_interval(1),
+        );
+        let mut compact_builder = DataBlockBuilder::new(
+            DataBlockBuilderOptions::default()
+                .with_restart_interval(1)
+                .with_compact_restarts(true),
+        );
+        for (key, value) in &test_data {
+            plain_builder.add(key, value);
+            compact_builder.add(key, value);
+        }
+
+        let plain_bytes =
+            plain_builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let compact_bytes =
+            compact_builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        assert!(compact_bytes.len() < plain_bytes.len());
+
+        let block = DataBlock::new(&compact_bytes, CompressionType::None)?;
+        let entries = block.get_entries()?;
+        assert_eq!(entries.len(), test_data.len());
+        for (i, entry) in entries.iter().enumerate() {
+            assert_eq!(entry.key, test_data[i].0);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_with_checksum_accepts_valid_trailer() -> Result<()> {
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(4));
+        builder.add(b"key", b"value");
+        let block_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let block = DataBlock::new_with_checksum(
+            &block_bytes,
+            CompressionType::None,
+            Some(ChecksumType::CRC32c),
+        )?;
+        let entries = block.get_entries()?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, b"key");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_with_checksum_rejects_corrupted_trailer() -> Result<()> {
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(4));
+        builder.add(b"key", b"value");
+        let mut block_bytes =
+            builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        // Flip a bit in the checksummed payload without touching the trailer.
+        let corrupt_at = block_bytes.len() - BLOCK_TRAILER_SIZE as usize - 1;
+        block_bytes[corrupt_at] ^= 0xff;
+
+        let result = DataBlock::new_with_checksum(
+            &block_bytes,
+            CompressionType::None,
+            Some(ChecksumType::CRC32c),
+        );
+        assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_without_checksum_skips_verification() -> Result<()> {
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(4));
+        builder.add(b"key", b"value");
+        let mut block_bytes =
+            builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let corrupt_at = block_bytes.len() - BLOCK_TRAILER_SIZE as usize - 1;
+        block_bytes[corrupt_at] ^= 0xff;
+
+        // DataBlock::new (no checksum_type) must still succeed even though
+        // the trailer no longer matches, since verification is opt-in.
+        let block = DataBlock::new(&block_bytes, CompressionType::None)?;
+        assert_eq!(block.get_entries()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_with_dict_none_behaves_like_new() -> Result<()> {
+        // CompressionType::None never consults the dictionary, so an
+        // uncompressed block should decode identically whether or not a
+        // dict is supplied — exercising new_with_dict's plumbing without
+        // needing a dictionary-aware codec.
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(4));
+        builder.add(b"key", b"value");
+        let block_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let with_dict = DataBlock::new_with_dict(&block_bytes, CompressionType::None, Some(b"ignored"))?;
+        let without_dict = DataBlock::new_with_dict(&block_bytes, CompressionType::None, None)?;
+
+        assert_eq!(with_dict.get_entries()?.len(), 1);
+        assert_eq!(
+            with_dict.get_entries()?[0].key,
+            without_dict.get_entries()?[0].key
+        );
+
+        Ok(())
+    }
+
+    // Elementwise and length-preserving, unlike e.g. reversal, so applying it
+    // to a byte string that has the raw 5-byte trailer appended after it (as
+    // production code does — see `from_decompressed`) and then inverting
+    // the whole thing back still recovers the original content unchanged;
+    // only the trailer bytes come back scrambled, which is fine since
+    // `from_decompressed` discards them anyway.
+    struct Xor(u8);
+
+    impl crate::compressor::Compressor for Xor {
+        fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(data.iter().map(|b| b ^ self.0).collect())
+        }
+
+        fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+            self.compress(data)
+        }
+    }
+
+    #[test]
+    fn test_new_with_dict_and_registry_resolves_a_custom_id() -> Result<()> {
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(4));
+        builder.add(b"key", b"value");
+
+        // Build the block uncompressed, then stand in for a foreign engine's
+        // block by re-compressing the content with a custom codec and a
+        // compression id outside CompressionType's range.
+        let plain_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let content = &plain_bytes[..plain_bytes.len() - BLOCK_TRAILER_SIZE as usize];
+        let mut custom_bytes = Xor(0x42).compress(content)?;
+        custom_bytes.push(200);
+        custom_bytes.extend_from_slice(&[0, 0, 0, 0]); // checksum unchecked below
+
+        let mut registry = crate::compressor::CompressorRegistry::new();
+        registry.register(200, Xor(0x42));
+
+        let block = DataBlock::new_with_dict_and_registry(&custom_bytes, 200, None, &registry)?;
+        let entries = block.get_entries()?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, b"key");
+        assert_eq!(entries[0].value, b"value");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_with_dict_and_registry_falls_back_to_built_ins() -> Result<()> {
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(4));
+        builder.add(b"key", b"value");
+        let block_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let registry = crate::compressor::CompressorRegistry::new();
+        let block = DataBlock::new_with_dict_and_registry(
+            &block_bytes,
+            CompressionType::None as u8,
+            None,
+            &registry,
+        )?;
+        assert_eq!(block.get_entries()?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek_binary_search_finds_exact_and_missing_keys() -> Result<()> {
+        // Small restart interval to exercise multiple restart points.
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(2));
+
+        let test_data = vec![
+            (b"a".to_vec(), b"1".to_vec()),
+            (b"c".to_vec(), b"2".to_vec()),
+            (b"e".to_vec(), b"3".to_vec()),
+            (b"g".to_vec(), b"4".to_vec()),
+            (b"i".to_vec(), b"5".to_vec()),
+            (b"k".to_vec(), b"6".to_vec()),
+        ];
+        for (key, value) in &test_data {
+            builder.add(key, value);
+        }
+
+        let block_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let mut reader = DataBlockReader::new(&block_bytes, CompressionType::None)?;
+
+        // Exact match.
+        assert!(reader.seek(b"e"));
+        assert!(reader.valid());
+        assert_eq!(reader.next().map(|e| e.key.clone()), Some(b"e".to_vec()));
+
+        // Between two keys lands on the next one.
+        assert!(reader.seek(b"f"));
+        assert_eq!(reader.next().map(|e| e.key.clone()), Some(b"g".to_vec()));
+
+        // Before the first key lands on the first entry.
+        assert!(reader.seek(b""));
+        assert_eq!(reader.next().map(|e| e.key.clone()), Some(b"a".to_vec()));
+
+        // After the last key finds nothing.
+        assert!(!reader.seek(b"z"));
+        assert!(!reader.valid());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek_for_prev_binary_search_finds_exact_and_missing_keys() -> Result<()> {
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(2));
+
+        let test_data = vec![
+            (b"a".to_vec(), b"1".to_vec()),
+            (b"c".to_vec(), b"2".to_vec()),
+            (b"e".to_vec(), b"3".to_vec()),
+            (b"g".to_vec(), b"4".to_vec()),
+        ];
+        for (key, value) in &test_data {
+            builder.add(key, value);
+        }
+
+        let block_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let mut reader = DataBlockReader::new(&block_bytes, CompressionType::None)?;
+
+        // Exact match.
+        assert!(reader.seek_for_prev(b"c"));
+        assert_eq!(reader.key(), Some(b"c".as_slice()));
+
+        // Between two keys lands on the smaller one.
+        assert!(reader.seek_for_prev(b"d"));
+        assert_eq!(reader.key(), Some(b"c".as_slice()));
+
+        // At or past the last key lands on the last entry.
+        assert!(reader.seek_for_prev(b"z"));
+        assert_eq!(reader.key(), Some(b"g".as_slice()));
+
+        // Before the first key finds nothing.
+        assert!(!reader.seek_for_prev(b""));
+        assert!(!reader.valid());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_and_value_reflect_the_current_entry_without_an_extra_next_call() -> Result<()> {
+        // `seek`/`seek_to_first`/`seek_to_last` all position `current_entry`
+        // directly on the entry they land on; `key`/`value` must read that
+        // same entry, not the one before it.
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(4));
+        builder.add(b"a", b"1");
+        builder.add(b"b", b"2");
+        builder.add(b"c", b"3");
+        let block_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let mut reader = DataBlockReader::new(&block_bytes, CompressionType::None)?;
+
+        reader.seek_to_first();
+        assert_eq!(reader.key(), Some(b"a".as_slice()));
+        assert_eq!(reader.value(), Some(b"1".as_slice()));
+
+        assert!(reader.seek(b"b"));
+        assert_eq!(reader.key(), Some(b"b".as_slice()));
+
+        reader.seek_to_last();
+        assert_eq!(reader.key(), Some(b"c".as_slice()));
+        assert_eq!(reader.value(), Some(b"3".as_slice()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prev_steps_backward_in_o1_without_rescanning_the_block() -> Result<()> {
+        let mut builder =
+            DataBlockBuilder::new(DataBlockBuilderOptions::default().with_restart_interval(4));
+        builder.add(b"a", b"1");
+        builder.add(b"b", b"2");
+        builder.add(b"c", b"3");
+        let block_bytes = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let mut reader = DataBlockReader::new(&block_bytes, CompressionType::None)?;
+
+        reader.seek_to_last();
+        assert_eq!(reader.key(), Some(b"c".as_slice()));
+
+        assert_eq!(reader.prev().map(|e| e.key.clone()), Some(b"b".to_vec()));
+        assert_eq!(reader.key(), Some(b"b".as_slice()));
+
+        assert_eq!(reader.prev().map(|e| e.key.clone()), Some(b"a".to_vec()));
+        assert_eq!(reader.key(), Some(b"a".as_slice()));
+
+        assert!(reader.prev().is_none());
+        assert!(!reader.valid());
+
+        Ok(())
+    }
 }
@@ -26,6 +26,9 @@ pub enum Error {
     #[error("Unsupported checksum type: {0}")]
     UnsupportedChecksumType(u8),
 
+    #[error("Unsupported entry type: {0}")]
+    UnsupportedEntryType(u8),
+
     #[error("Unsupported format version: {0}")]
     UnsupportedFormatVersion(u32),
 
@@ -61,6 +64,40 @@ pub enum Error {
 
     #[error("Unsupported operation: {0}")]
     UnsupportedOperation(String),
+
+    #[error(
+        "Checksum mismatch at offset {offset}: expected {expected:#x}, got {actual:#x}"
+    )]
+    ChecksumMismatch {
+        offset: u64,
+        expected: u32,
+        actual: u32,
+    },
+
+    #[error(
+        "Footer checksum mismatch at offset {offset}: expected {expected:#x}, computed {computed:#x}"
+    )]
+    FooterChecksumMismatch {
+        offset: u64,
+        expected: u32,
+        computed: u32,
+    },
+
+    #[error("Reserved field at offset {offset} must be zero, got {value:#x}")]
+    ReservedFieldNonZero { offset: u64, value: u64 },
+
+    #[error("Bad extended magic at offset {offset}: {actual:?}")]
+    BadExtendedMagic { offset: u64, actual: [u8; 4] },
+
+    #[error(
+        "Truncated field `{field}` at offset {offset}: need {need} bytes, have {have}"
+    )]
+    TruncatedField {
+        offset: u64,
+        field: &'static str,
+        need: usize,
+        have: usize,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,14 +1,11 @@
 // Copyright 2024 YaleDB Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use byteorder::{ByteOrder, LittleEndian};
-
 use crate::block_handle::BlockHandle;
+use crate::block_source::BlockSource;
+use crate::cursor::BinCursor;
 use crate::error::{Error, Result};
-use crate::types::{
-    ChecksumType, FOOTER_SIZE, LEGACY_MAGIC_NUMBER, ROCKSDB_MAGIC_NUMBER,
-    checksum_modifier_for_context,
-};
+use crate::types::{ChecksumType, FOOTER_SIZE, LEGACY_MAGIC_NUMBER, ROCKSDB_MAGIC_NUMBER};
 use std::io::{Cursor, Read, Seek, SeekFrom};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -24,82 +21,101 @@ pub struct Footer {
     pub base_context_checksum: Option<u32>,
 }
 
-struct ReverseCursor<'a> {
+/// Reads fixed-width little-endian fields from the tail of a footer buffer
+/// backward, mirroring the order those fields appear on disk (footer fields
+/// are laid out to be parsed from the end). `base_offset` is the absolute
+/// file offset of `data[0]`, so every error can report exactly which byte
+/// position the offending field starts at rather than a cursor-relative one.
+pub(crate) struct ReverseCursor<'a> {
     data: &'a [u8],
     pos: usize,
+    base_offset: u64,
 }
 
 impl<'a> ReverseCursor<'a> {
-    pub fn new(data: &'a [u8]) -> Self {
+    pub fn new(data: &'a [u8], base_offset: u64) -> Self {
         Self {
             data,
             pos: data.len(),
+            base_offset,
         }
     }
+}
 
-    pub fn read_u64(&mut self) -> Result<u64> {
-        if self.pos < 8 {
-            return Err(Error::DataCorruption(
-                "Unable to read data from cursor, because it's end".to_string(),
-            ));
-        }
-
-        self.pos -= 8;
-
-        Ok(LittleEndian::read_u64(&self.data[self.pos..self.pos + 8]))
+impl<'a> BinCursor for ReverseCursor<'a> {
+    /// Absolute file offset the cursor is currently positioned at, i.e. the
+    /// start of the field most recently read.
+    fn offset(&self) -> u64 {
+        self.base_offset + self.pos as u64
     }
 
-    pub fn read_i32(&mut self) -> Result<i32> {
-        if self.pos < 4 {
-            return Err(Error::DataCorruption(
-                "Unable to read data from cursor, because it's end".to_string(),
-            ));
-        }
-
-        self.pos -= 4;
-
-        Ok(LittleEndian::read_i32(&self.data[self.pos..self.pos + 4]))
+    fn remaining(&self) -> usize {
+        self.pos
     }
 
-    pub fn read_u32(&mut self) -> Result<u32> {
-        if self.pos < 4 {
-            return Err(Error::DataCorruption(
-                "Unable to read data from cursor, because it's end".to_string(),
-            ));
+    fn read_bytes(&mut self, buf: &mut [u8], field: &'static str) -> Result<()> {
+        if self.pos < buf.len() {
+            return Err(Error::TruncatedField {
+                offset: self.offset(),
+                field,
+                need: buf.len(),
+                have: self.pos,
+            });
         }
 
-        self.pos -= 4;
+        self.pos -= buf.len();
+        buf.copy_from_slice(&self.data[self.pos..self.pos + buf.len()]);
 
-        Ok(LittleEndian::read_u32(&self.data[self.pos..self.pos + 4]))
+        Ok(())
     }
 
-    pub fn read_u8(&mut self) -> Result<u8> {
-        if self.pos < 1 {
-            return Err(Error::DataCorruption(
-                "Unable to read data from cursor, because it's end".to_string(),
-            ));
+    /// Decode a varint64 ending at the cursor's current position. Varints
+    /// in this format are always written forward (continuation bit set on
+    /// every byte but the last), so walking backward means first locating
+    /// the terminal byte immediately before the cursor, then continuing to
+    /// walk back while earlier bytes still carry a continuation bit, and
+    /// finally replaying the bytes found in their on-disk (forward) order.
+    fn read_varint64(&mut self, field: &'static str) -> Result<u64> {
+        let end = self.pos;
+        if end == 0 {
+            return Err(Error::TruncatedField {
+                offset: self.offset(),
+                field,
+                need: 1,
+                have: 0,
+            });
         }
 
-        self.pos -= 1;
+        let mut start = end - 1;
+        if self.data[start] & 0x80 != 0 {
+            return Err(Error::InvalidVarint);
+        }
 
-        Ok(self.data[self.pos])
-    }
+        while start > 0 && end - start < 10 && self.data[start - 1] & 0x80 != 0 {
+            start -= 1;
+        }
 
-    pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
-        if self.pos < buf.len() {
-            return Err(Error::DataCorruption(
-                "Unable to read data from cursor, because it's end".to_string(),
-            ));
+        if end - start > 10 {
+            return Err(Error::InvalidVarint);
         }
 
-        self.pos -= buf.len();
-        buf.copy_from_slice(&self.data[self.pos..self.pos + buf.len()]);
+        let mut result = 0u64;
+        let mut shift = 0;
+        for &byte in &self.data[start..end] {
+            result |= ((byte & 0x7f) as u64) << shift;
+            shift += 7;
+        }
 
-        Ok(())
+        self.pos = start;
+        Ok(result)
     }
 }
 
 impl Footer {
+    /// Read the footer with a single tail read of up to 53 bytes (the
+    /// largest footer layout, v6+) rather than the several seek/peek-then-
+    /// reread passes a naive reader would need to first discover which
+    /// layout is present.
     pub fn read_from<R: Read + Seek>(reader: &mut R) -> Result<Self> {
         let file_size = reader.seek(SeekFrom::End(0))?;
 
@@ -108,50 +124,118 @@ impl Footer {
             return Err(Error::FileTooSmall);
         }
 
-        // First, check for the new magic number at position -8
-        reader.seek(SeekFrom::End(-8))?;
-        let mut magic_bytes = [0u8; 8];
-        reader.read_exact(&mut magic_bytes)?;
-        let magic = u64::from_le_bytes(magic_bytes);
+        let max_footer_size = 53u64.min(file_size);
+        let mut tail = [0u8; 53];
+        let tail = &mut tail[..max_footer_size as usize];
+        reader.seek(SeekFrom::End(-(max_footer_size as i64)))?;
+        reader.read_exact(tail)?;
+
+        let magic = u64::from_le_bytes(tail[tail.len() - 8..].try_into().unwrap());
 
         if magic == ROCKSDB_MAGIC_NUMBER {
-            // New format - read format version to determine footer size
-            reader.seek(SeekFrom::End(-12))?;
-            let mut version_bytes = [0u8; 4];
-            reader.read_exact(&mut version_bytes)?;
+            // `format_version` is always the 4 bytes right before the magic,
+            // in both the 49-byte (v1-v5) and 53-byte (v6+) layouts, so its
+            // position can be read straight out of the tail before
+            // committing to either footer size.
+            let footer_size = Self::footer_size_for_tail(tail);
+            if file_size < footer_size {
+                return Err(Error::FileTooSmall);
+            }
 
-            let footer_size = 53;
+            let footer_data = &tail[tail.len() - footer_size as usize..];
+            let input_offset = file_size - footer_size;
+            Self::decode_view(footer_data, input_offset)
+        } else {
+            // The legacy magic sits 48 bytes from the end; the tail read
+            // above already covers that range whenever file_size >= 53, but
+            // for a file sized exactly 48-52 the buffer holds fewer than 53
+            // bytes, so re-derive the legacy magic's position within it.
+            let legacy_magic_at = tail.len() - 48;
+            let legacy_magic =
+                u64::from_le_bytes(tail[legacy_magic_at..legacy_magic_at + 8].try_into().unwrap());
 
-            // Read the full footer
-            if file_size < footer_size as u64 {
+            if legacy_magic == LEGACY_MAGIC_NUMBER {
+                let footer_data = &tail[legacy_magic_at..];
+                let input_offset = file_size - 48;
+                Self::decode_view(footer_data, input_offset)
+            } else {
+                Err(Error::InvalidMagicNumber(magic))
+            }
+        }
+    }
+
+    /// Given a tail buffer already known to end in [`ROCKSDB_MAGIC_NUMBER`],
+    /// determine whether it holds a 49-byte (v1-v5) or 53-byte (v6+) footer
+    /// by reading `format_version` out of the 4 bytes immediately preceding
+    /// the magic — that field sits at the same fixed offset from the end in
+    /// both layouts (see [`Footer::encode_to_bytes`]), so this doesn't
+    /// require first guessing the size. Callers must ensure `tail.len() >=
+    /// 12` (guaranteed here since both layouts are well over that, and the
+    /// minimum tail read is 48 bytes).
+    fn footer_size_for_tail(tail: &[u8]) -> u64 {
+        let format_version =
+            u32::from_le_bytes(tail[tail.len() - 12..tail.len() - 8].try_into().unwrap());
+        if format_version >= 6 {
+            53
+        } else {
+            FOOTER_SIZE as u64
+        }
+    }
+
+    /// Read the footer from a [`BlockSource`] rather than a `Read + Seek` stream.
+    ///
+    /// This is the counterpart of [`Footer::read_from`] for `SstReader<S>`: it
+    /// performs the same single tail read of at most 53 bytes, but through
+    /// [`BlockSource::read_at`] against `source` instead of seeking a stream.
+    pub fn read_from_source<S: BlockSource + ?Sized>(source: &S) -> Result<Self> {
+        let file_size = source.len();
+
+        if file_size < 48 {
+            return Err(Error::FileTooSmall);
+        }
+
+        let max_footer_size = 53u64.min(file_size);
+        let mut buf = [0u8; 53];
+        let tail = &mut buf[..max_footer_size as usize];
+        source.read_at(file_size - max_footer_size, tail)?;
+
+        let magic = u64::from_le_bytes(tail[tail.len() - 8..].try_into().unwrap());
+
+        if magic == ROCKSDB_MAGIC_NUMBER {
+            let footer_size = Self::footer_size_for_tail(tail);
+            if file_size < footer_size {
                 return Err(Error::FileTooSmall);
             }
-            reader.seek(SeekFrom::End(-(footer_size as i64)))?;
-            let mut footer_data = vec![0u8; footer_size];
-            reader.read_exact(&mut footer_data)?;
 
-            let input_offset = file_size - (footer_size as u64);
-            Self::decode_from_bytes(&footer_data, input_offset)
+            let footer_data = &tail[tail.len() - footer_size as usize..];
+            let input_offset = file_size - footer_size;
+            Self::decode_view(footer_data, input_offset)
         } else {
-            // Check for legacy magic number at position -48
-            reader.seek(SeekFrom::End(-48))?;
-            let mut legacy_magic_bytes = [0u8; 8];
-            reader.read_exact(&mut legacy_magic_bytes)?;
-            let legacy_magic = u64::from_le_bytes(legacy_magic_bytes);
+            let legacy_magic_at = tail.len() - 48;
+            let legacy_magic =
+                u64::from_le_bytes(tail[legacy_magic_at..legacy_magic_at + 8].try_into().unwrap());
 
             if legacy_magic == LEGACY_MAGIC_NUMBER {
-                // Legacy format (v0) - 48-byte footer
-                reader.seek(SeekFrom::End(-48))?;
-                let mut footer_data = vec![0u8; 48];
-                reader.read_exact(&mut footer_data)?;
+                let footer_data = &tail[legacy_magic_at..];
                 let input_offset = file_size - 48;
-                Self::decode_from_bytes(&footer_data, input_offset)
+                Self::decode_view(footer_data, input_offset)
             } else {
                 Err(Error::InvalidMagicNumber(magic))
             }
         }
     }
 
+    /// Decode a footer from a borrowed byte slice with no heap allocation:
+    /// the v6+ checksum is verified by zeroing its 4 bytes in a stack-local
+    /// copy of the (at most 53-byte) footer rather than cloning `data` into
+    /// a `Vec`. This is the allocation-free counterpart `read_from`/
+    /// `read_from_source` both call once they've located the footer's tail
+    /// bytes; `decode_from_bytes` is kept as an alias for existing callers
+    /// and tests.
+    pub fn decode_view(data: &[u8], input_offset: u64) -> Result<Self> {
+        Self::decode_from_bytes(data, input_offset)
+    }
+
     pub fn decode_from_bytes(data: &[u8], input_offset: u64) -> Result<Self> {
         // Check for magic number at the end
         if data.len() < 12 {
@@ -161,8 +245,8 @@ impl Footer {
         // +---------------------------------------------------------------+
         // | checksum (1B) | part2 (40B) | format_version (4B) | magic (8B)|
         // +---------------------------------------------------------------+
-        let mut cursor = ReverseCursor::new(&data);
-        let magic = cursor.read_u64()?;
+        let mut cursor = ReverseCursor::new(&data, input_offset);
+        let magic = cursor.read_u64("magic number")?;
 
         // Handle legacy format (v0) first
         if magic == LEGACY_MAGIC_NUMBER {
@@ -188,30 +272,23 @@ impl Footer {
             return Err(Error::InvalidMagicNumber(magic));
         }
 
-        let format_version = cursor.read_u32()?;
+        let format_version = cursor.read_u32("format_version")?;
         if format_version >= 6 {
             // second part!
             // 8 + 16 = 24 bytes padded, reserved
             {
                 // 16 bytes of unchecked reserved padding
                 let mut skip_bytes = [0u8; 16];
-                cursor.read_exact(&mut skip_bytes).map_err(|err| {
-                    Error::DataCorruption(format!(
-                        "Unable to read 16 bytes for reserved padding: {:?}",
-                        err
-                    ))
-                })?;
+                cursor.read_bytes(&mut skip_bytes, "reserved padding")?;
 
                 // 8 bytes of checked reserved padding (expected to be zero unless using a
                 // future feature).
-                let reserved = cursor.read_u64().map_err(|err| {
-                    Error::DataCorruption(format!("Unable to read reserved 8 bytes: {:?}", err))
-                })?;
+                let reserved = cursor.read_u64("reserved")?;
                 if reserved != 0 {
-                    return Err(Error::Unsupported(format!(
-                        "File uses a future feature not supported in this version: {}",
-                        reserved
-                    )));
+                    return Err(Error::ReservedFieldNonZero {
+                        offset: cursor.offset(),
+                        value: reserved,
+                    });
                 }
             }
 
@@ -219,53 +296,50 @@ impl Footer {
             let adjustment = 5;
             let footer_offset = input_offset - adjustment;
 
-            let metaindex_size = cursor.read_i32()? as u64;
+            let metaindex_size = cursor.read_i32("metaindex size")? as u64;
             let metaindex_handle = BlockHandle::new(footer_offset - metaindex_size, metaindex_size);
 
             // Index handle is null for v6+
             let index_handle = BlockHandle::new(0, 0);
 
-            let base_context_checksum = cursor.read_i32().map_err(|err| {
-                Error::DataCorruption(format!("Unable to read base context checksum: {:?}", err))
-            })? as u32;
+            let base_context_checksum = cursor.read_i32("base context checksum")? as u32;
 
-            let stored_checksum = cursor.read_i32().map_err(|err| {
-                Error::DataCorruption(format!("Unable to read stored checksum: {:?}", err))
-            })? as u32;
+            let stored_checksum = cursor.read_i32("footer checksum")? as u32;
 
             {
                 let mut magic_bytes = [0u8; 4];
-                cursor.read_exact(&mut magic_bytes).map_err(|err| {
-                    Error::DataCorruption(format!("Unable to read footer magic bytes: {:?}", err))
-                })?;
+                cursor.read_bytes(&mut magic_bytes, "extended magic")?;
 
                 // Check for extended magiс
                 if magic_bytes != [0x3e, 0x00, 0x7a, 0x00] {
-                    return Err(Error::DataCorruption(format!(
-                        "Invalid extended magic, actual: {:?}",
-                        magic_bytes
-                    )));
+                    return Err(Error::BadExtendedMagic {
+                        offset: cursor.offset(),
+                        actual: magic_bytes,
+                    });
                 }
             }
 
-            let checksum_type = ChecksumType::try_from(cursor.read_u8()?)?;
+            let checksum_type = ChecksumType::try_from(cursor.read_u8("checksum type")?)?;
 
-            // Perform checksum verification
-            let mut footer_copy = data.to_vec();
-            // Zero out the checksum field (bytes 5-8 from the start)
+            // Perform checksum verification without a heap allocation: copy
+            // the (fixed-size, 53-byte) footer into a stack array and zero
+            // out the checksum field (bytes 5-8 from the start) there.
+            let mut footer_copy = [0u8; 53];
+            footer_copy[..data.len()].copy_from_slice(data);
             footer_copy[5..9].fill(0);
 
-            let computed_checksum = checksum_type.calculate(&footer_copy);
-            let modified_checksum = computed_checksum.wrapping_add(checksum_modifier_for_context(
+            let modified_checksum = checksum_type.calculate_with_context(
+                &footer_copy[..data.len()],
                 base_context_checksum,
                 input_offset,
-            ));
+            );
 
             if modified_checksum != stored_checksum {
-                return Err(Error::DataCorruption(format!(
-                    "Footer checksum mismatch at offset {}: expected {:#x}, computed {:#x}",
-                    input_offset, stored_checksum, modified_checksum
-                )));
+                return Err(Error::FooterChecksumMismatch {
+                    offset: input_offset,
+                    expected: stored_checksum,
+                    computed: modified_checksum,
+                });
             }
 
             Ok(Footer {
@@ -335,9 +409,9 @@ impl Footer {
             data.extend(&ROCKSDB_MAGIC_NUMBER.to_le_bytes());
 
             // Calculate checksum with the provided offset
-            let computed_checksum = self.checksum_type.calculate(&data);
-            let modified_checksum = computed_checksum
-                .wrapping_add(checksum_modifier_for_context(base_context_checksum, offset));
+            let modified_checksum =
+                self.checksum_type
+                    .calculate_with_context(&data, base_context_checksum, offset);
 
             // Write the checksum to bytes 5-8 (where the checksum field is)
             data[5..9].copy_from_slice(&(modified_checksum as i32).to_le_bytes());
@@ -366,6 +440,19 @@ impl Footer {
             Ok(data)
         }
     }
+
+    /// Whether blocks in this table carry the standard 5-byte trailer
+    /// (a `compression_type` byte followed by a checksum) after their
+    /// content. Every footer variant this crate currently decodes uses the
+    /// standard trailer — even the "legacy" v0 footer forces
+    /// `checksum_type: CRC32c` rather than going trailer-less — so this
+    /// always returns `true` today. It exists as the seam a genuinely
+    /// trailer-less format would hook into, keyed off the same
+    /// magic-number-driven decision [`Footer::read_from`] and
+    /// [`Footer::read_from_source`] already make.
+    pub fn has_block_trailers(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -573,6 +660,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_footer_decode_view_matches_decode_from_bytes() -> Result<()> {
+        let original = Footer {
+            checksum_type: ChecksumType::XXH3,
+            metaindex_handle: BlockHandle::new(1000, 500),
+            index_handle: BlockHandle::new(1500, 200),
+            format_version: 5,
+            base_context_checksum: None,
+        };
+
+        let footer_offset = 1500;
+        let encoded = original.encode_to_bytes(footer_offset)?;
+
+        let via_view = Footer::decode_view(&encoded, footer_offset)?;
+        let via_legacy_name = Footer::decode_from_bytes(&encoded, footer_offset)?;
+        assert_eq!(via_view, via_legacy_name);
+        Ok(())
+    }
+
     #[test]
     fn test_footer_v6_encoding_with_offset() -> Result<()> {
         // Test that encoding with different offsets produces different checksums
@@ -1,13 +1,81 @@
 use crate::block_handle::BlockHandle;
+use crate::comparator::{BytewiseComparator, Comparator};
 use crate::compression::decompress;
 use crate::error::{Error, Result};
-use crate::types::CompressionType;
-use byteorder::{LittleEndian, ReadBytesExt};
+use crate::types::{ChecksumType, CompressionType};
+use byteorder::{ByteOrder, LittleEndian, ReadBytesExt};
 use std::io::Cursor;
+use std::sync::Arc;
 
 pub struct IndexEntry {
     pub key: Vec<u8>,
     pub block_handle: BlockHandle,
+    /// The referenced data block's first key, present only when the index
+    /// was parsed with [`IndexValueFormat::DeltaWithFirstKey`] (RocksDB's
+    /// `kBinarySearchWithFirstKey`). Consulted by
+    /// [`IndexBlock::find_block_for_exact_key`] to rule out a block for an
+    /// exact-match lookup without reading it; a range-scan seek still needs
+    /// the block regardless (see [`IndexBlock::find_block_for_key`]).
+    pub first_key: Option<Vec<u8>>,
+}
+
+/// How an index entry's value bytes (everything after the key) are encoded.
+/// Plain SST index blocks always use [`Self::Full`]; RocksDB's newer
+/// formats can shrink the index by delta-encoding the block handle's offset
+/// against the previous entry's, and/or prefixing each value with the
+/// referenced block's first key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexValueFormat {
+    /// A varint64 `offset` followed by a varint64 `size` — the format every
+    /// other part of this crate has always assumed.
+    Full,
+    /// The first entry after each restart point stores a full `(offset,
+    /// size)` pair, same as [`Self::Full`]; every other entry stores only a
+    /// varint64 `size`, with `offset` computed as the previous entry's
+    /// `offset + size`.
+    Delta,
+    /// Same delta encoding as [`Self::Delta`], but each value is preceded
+    /// by a length-prefixed (varint32) first key for the data block the
+    /// handle points to — RocksDB's `kBinarySearchWithFirstKey` index type.
+    DeltaWithFirstKey,
+}
+
+impl Default for IndexValueFormat {
+    fn default() -> Self {
+        IndexValueFormat::Full
+    }
+}
+
+impl IndexValueFormat {
+    /// Index value delta encoding was introduced as part of RocksDB's
+    /// format_version 4 and is used whenever the table's format version is
+    /// at least that, so a caller that only knows `format_version` (e.g.
+    /// from [`crate::metaindex::TableProperties::format_version`]) can pick
+    /// [`Self::Delta`] vs [`Self::Full`] without inspecting anything else.
+    /// `kBinarySearchWithFirstKey` isn't recorded anywhere in
+    /// `rocksdb.properties`, so [`Self::DeltaWithFirstKey`] can't be
+    /// detected this way — pass it explicitly to [`IndexBlock::with_format`]
+    /// when the table's index type is known out of band.
+    pub fn from_format_version(format_version: u64) -> Self {
+        if format_version >= 4 {
+            IndexValueFormat::Delta
+        } else {
+            IndexValueFormat::Full
+        }
+    }
+}
+
+/// A single decoded index entry, plus the byte offset just past it. Kept as
+/// a named struct rather than a growing tuple now that there's a key, a
+/// handle, an optional first key, and the entry's declared
+/// `shared_key_len` to carry between [`IndexBlock::decode_entry`] and its
+/// callers.
+struct DecodedEntry {
+    key: Vec<u8>,
+    handle: BlockHandle,
+    next_pos: usize,
+    shared_key_len: u32,
+    first_key: Option<Vec<u8>>,
 }
 
 pub struct IndexBlock {
@@ -15,10 +83,239 @@ pub struct IndexBlock {
     restart_offset: usize,
     num_restarts: u32,
     restart_points: Vec<u32>,
+    comparator: Arc<dyn Comparator>,
+    value_format: IndexValueFormat,
+}
+
+/// A lazy cursor over an [`IndexBlock`]'s entries, decoding each
+/// `(shared, unshared, value)` triple on demand instead of materializing a
+/// `Vec<IndexEntry>` up front. `seek` does a restart-point binary search to
+/// jump near the target before scanning forward, so looking up one key in a
+/// large index block no longer costs an allocation per entry in the block.
+/// [`IndexBlock::get_all_block_handles`] and [`IndexBlock::find_block_for_key`]
+/// are both built on top of this iterator, so there is one decode path.
+pub struct IndexBlockIter<'a> {
+    block: &'a IndexBlock,
+    pos: usize,
+    last_key: Vec<u8>,
+    current_handle: Option<BlockHandle>,
+    current_first_key: Option<Vec<u8>>,
+    /// The previous entry's handle, for [`IndexValueFormat::Delta`]/
+    /// [`IndexValueFormat::DeltaWithFirstKey`] offset reconstruction.
+    /// Tracked separately from `current_handle`, which must keep holding
+    /// the last entry scanned across a restart boundary (for
+    /// [`IndexBlock::find_block_for_key`]'s past-the-end fallback) even
+    /// though delta decoding resets at every restart.
+    delta_prev_handle: Option<BlockHandle>,
+}
+
+impl<'a> IndexBlockIter<'a> {
+    fn new(block: &'a IndexBlock) -> Self {
+        IndexBlockIter {
+            block,
+            pos: block.initial_pos(),
+            last_key: Vec::new(),
+            current_handle: None,
+            current_first_key: None,
+            delta_prev_handle: None,
+        }
+    }
+
+    /// Reset the cursor to the block's first entry.
+    pub fn seek_to_first(&mut self) {
+        self.pos = self.block.initial_pos();
+        self.last_key.clear();
+        self.current_handle = None;
+        self.current_first_key = None;
+        self.delta_prev_handle = None;
+    }
+
+    /// Position the cursor at the first entry whose key is `>= target_key`.
+    /// Leaves the cursor exhausted (next `next()` call returns `None`) if no
+    /// such entry exists, but still remembers the block's last entry
+    /// internally, for callers (like [`IndexBlock::find_block_for_key`])
+    /// that want a fallback handle in that case.
+    pub fn seek(&mut self, target_key: &[u8]) -> Result<()> {
+        self.pos = self.block.restart_for_key(target_key)?;
+        self.last_key.clear();
+        self.current_handle = None;
+        self.current_first_key = None;
+        self.delta_prev_handle = None;
+
+        while self.pos < self.block.restart_offset {
+            let entry_pos = self.pos;
+            let last_key_before = self.last_key.clone();
+            let prev_handle_before = self.delta_prev_handle.clone();
+
+            if self.block.is_restart_point(self.pos as u32) {
+                self.last_key.clear();
+                self.delta_prev_handle = None;
+            }
+
+            let decoded = self.block.decode_entry(
+                self.pos,
+                &self.last_key,
+                self.delta_prev_handle.as_ref(),
+            )?;
+
+            if self.block.comparator.compare(&decoded.key, target_key) != std::cmp::Ordering::Less
+            {
+                // Leave the cursor positioned just before this entry, with
+                // the prefix-sharing and delta-handle context it needs, so
+                // `next()` decodes it fresh instead of skipping past it.
+                self.pos = entry_pos;
+                self.last_key = last_key_before;
+                self.delta_prev_handle = prev_handle_before;
+                return Ok(());
+            }
+
+            self.last_key = decoded.key;
+            self.current_handle = Some(decoded.handle.clone());
+            self.current_first_key = decoded.first_key;
+            self.delta_prev_handle = Some(decoded.handle);
+            self.pos = decoded.next_pos;
+        }
+
+        // No entry is >= target_key: leave the cursor exhausted, with
+        // `current_handle` retaining the last entry scanned.
+        self.pos = self.block.restart_offset;
+        Ok(())
+    }
+
+    /// Advance to the next entry, returning its key and block handle, or
+    /// `None` once the block is exhausted.
+    pub fn next(&mut self) -> Result<Option<(&[u8], &BlockHandle)>> {
+        if self.pos >= self.block.restart_offset {
+            return Ok(None);
+        }
+
+        if self.block.is_restart_point(self.pos as u32) {
+            self.last_key.clear();
+            self.delta_prev_handle = None;
+        }
+
+        let decoded =
+            self.block
+                .decode_entry(self.pos, &self.last_key, self.delta_prev_handle.as_ref())?;
+        self.last_key = decoded.key;
+        self.current_handle = Some(decoded.handle.clone());
+        self.current_first_key = decoded.first_key;
+        self.delta_prev_handle = Some(decoded.handle);
+        self.pos = decoded.next_pos;
+
+        Ok(Some((
+            self.last_key.as_slice(),
+            self.current_handle.as_ref().unwrap(),
+        )))
+    }
 }
 
 impl IndexBlock {
-    pub fn new(compressed_data: &[u8], compression_type: CompressionType) -> Result<Self> {
+    /// Parse an index block ordered by raw byte comparison, after verifying
+    /// its 5-byte trailer checksum against `checksum_type` — see
+    /// [`Self::verify_trailer_checksum`]. Returns
+    /// [`Error::ChecksumMismatch`] on a mismatch instead of silently parsing
+    /// a corrupted block. Callers that already verified the block elsewhere
+    /// (e.g. [`crate::sst_reader::SstReader::read_block`] via
+    /// `ReadOptions::verify_index_blocks`, which applies the
+    /// format_version-aware context checksum) should use
+    /// [`Self::new_unchecked`] instead, to avoid paying for a second,
+    /// context-unaware check.
+    pub fn new(
+        compressed_data: &[u8],
+        compression_type: CompressionType,
+        checksum_type: ChecksumType,
+    ) -> Result<Self> {
+        Self::verify_trailer_checksum(compressed_data, checksum_type)?;
+        Self::new_unchecked(compressed_data, compression_type)
+    }
+
+    /// Parse an index block ordered by raw byte comparison, without
+    /// verifying its trailer checksum — the lenient behavior `new` used to
+    /// have unconditionally. For a table whose index keys are RocksDB
+    /// internal keys, use [`Self::with_comparator`] with an
+    /// [`crate::comparator::InternalKeyComparator`] instead.
+    pub fn new_unchecked(compressed_data: &[u8], compression_type: CompressionType) -> Result<Self> {
+        Self::with_comparator(
+            compressed_data,
+            compression_type,
+            Arc::new(BytewiseComparator),
+        )
+    }
+
+    /// Recompute the checksum over `compressed_data[..len-4]` (the
+    /// compressed content plus the compression-type byte) using
+    /// `checksum_type`'s plain, non-context algorithm, and compare it
+    /// against the stored little-endian `u32` in the trailer's last 4
+    /// bytes. `ChecksumType::None` always passes, matching the crate's
+    /// other checksum entry points. The file offset in a resulting
+    /// [`Error::ChecksumMismatch`] is always 0: unlike
+    /// [`crate::block_trailer::verify_block`], `IndexBlock` has no notion
+    /// of its own position in the file, so this check can't apply the
+    /// format_version >= 6 context-checksum modifier — it's a
+    /// last-line-of-defense for callers that construct an `IndexBlock`
+    /// directly from bytes, not a replacement for `verify_block`.
+    fn verify_trailer_checksum(
+        compressed_data: &[u8],
+        checksum_type: ChecksumType,
+    ) -> Result<()> {
+        if checksum_type == ChecksumType::None {
+            return Ok(());
+        }
+
+        if compressed_data.len() < 5 {
+            return Err(Error::InvalidBlockFormat(
+                "Index block too small to contain a trailer".to_string(),
+            ));
+        }
+
+        let checksummed_len = compressed_data.len() - 4;
+        let stored = LittleEndian::read_u32(&compressed_data[checksummed_len..]);
+        let computed = checksum_type.calculate(&compressed_data[..checksummed_len]);
+
+        if computed != stored {
+            return Err(Error::ChecksumMismatch {
+                offset: 0,
+                expected: stored,
+                actual: computed,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Parse an index block whose keys are ordered by `comparator` rather
+    /// than raw byte comparison — e.g. an [`crate::comparator::InternalKeyComparator`] for a
+    /// table whose index keys are RocksDB internal keys (user key + 8-byte
+    /// sequence/type trailer). Assumes [`IndexValueFormat::Full`] values;
+    /// for a table using delta-encoded index values or
+    /// `kBinarySearchWithFirstKey`, use [`Self::with_format`] instead.
+    pub fn with_comparator(
+        compressed_data: &[u8],
+        compression_type: CompressionType,
+        comparator: Arc<dyn Comparator>,
+    ) -> Result<Self> {
+        Self::with_format(
+            compressed_data,
+            compression_type,
+            comparator,
+            IndexValueFormat::Full,
+        )
+    }
+
+    /// Parse an index block whose keys are ordered by `comparator` and whose
+    /// values are encoded per `value_format` — the most general constructor,
+    /// all others delegate here. `value_format` can be detected from the
+    /// table's format version via [`IndexValueFormat::from_format_version`]
+    /// for the `Full`/`Delta` choice, but `DeltaWithFirstKey` must be passed
+    /// explicitly since `kBinarySearchWithFirstKey` isn't recorded in
+    /// `rocksdb.properties`.
+    pub fn with_format(
+        compressed_data: &[u8],
+        compression_type: CompressionType,
+        comparator: Arc<dyn Comparator>,
+        value_format: IndexValueFormat,
+    ) -> Result<Self> {
         let raw_data = decompress(compressed_data, compression_type)?;
 
         // RocksDB blocks have a 5-byte trailer: compression_type (1) + checksum (4)
@@ -48,6 +345,8 @@ impl IndexBlock {
                 restart_offset: data_len,
                 num_restarts: 1,
                 restart_points: vec![0],
+                comparator,
+                value_format,
             });
         }
 
@@ -59,6 +358,8 @@ impl IndexBlock {
                 restart_offset: data_len,
                 num_restarts: 1,
                 restart_points: vec![0],
+                comparator,
+                value_format,
             });
         }
 
@@ -81,97 +382,200 @@ impl IndexBlock {
             restart_offset,
             num_restarts,
             restart_points,
+            comparator,
+            value_format,
         })
     }
 
     pub fn get_entries(&self) -> Result<Vec<IndexEntry>> {
         let mut entries = Vec::new();
-        let mut cursor = Cursor::new(&self.data);
-        let mut last_key = Vec::new();
+        let mut iter = self.iter();
+        while let Some((key, block_handle)) = iter.next()? {
+            entries.push(IndexEntry {
+                key: key.to_vec(),
+                block_handle: block_handle.clone(),
+                first_key: iter.current_first_key.clone(),
+            });
+        }
+        Ok(entries)
+    }
+
+    /// A lazy cursor over this block's entries — see [`IndexBlockIter`].
+    /// Starts positioned at the block's first entry, same as calling
+    /// [`IndexBlockIter::seek_to_first`].
+    pub fn iter(&self) -> IndexBlockIter<'_> {
+        IndexBlockIter::new(self)
+    }
 
-        // Try to find a valid starting point by looking for an entry with shared_len=0
-        let mut start_pos = 0;
-        if self.data.len() > 0 && self.data[0] != 0 {
-            // First byte is not 0 (shared_len), so look for a restart point
+    /// Find a safe starting offset for a full forward scan, falling back to
+    /// the nearest restart point with `shared_key_len == 0` if the block
+    /// doesn't start with one (same heuristic [`Self::get_entries`] always
+    /// used, kept here so [`IndexBlockIter::seek_to_first`] matches it).
+    fn initial_pos(&self) -> usize {
+        let mut pos = 0;
+        if !self.data.is_empty() && self.data[0] != 0 {
             for &restart_pos in &self.restart_points {
-                if restart_pos < self.data.len() as u32 && restart_pos > 0 {
-                    if (restart_pos as usize) < self.data.len()
-                        && self.data[restart_pos as usize] == 0
-                    {
-                        start_pos = restart_pos as usize;
-                        break;
-                    }
+                if restart_pos > 0
+                    && (restart_pos as usize) < self.data.len()
+                    && self.data[restart_pos as usize] == 0
+                {
+                    pos = restart_pos as usize;
+                    break;
                 }
             }
         }
+        pos
+    }
 
-        cursor.set_position(start_pos as u64);
+    /// Binary-search `self.restart_points` for the last restart whose key is
+    /// `<= target_key`, returning its byte offset. Shared by
+    /// [`Self::find_block_for_key`] and [`IndexBlockIter::seek`].
+    fn restart_for_key(&self, target_key: &[u8]) -> Result<usize> {
+        if self.restart_points.is_empty() {
+            return Ok(self.restart_offset);
+        }
 
-        while (cursor.position() as usize) < self.restart_offset {
-            let entry_start = cursor.position();
+        let mut left = 0usize;
+        let mut right = self.restart_points.len() - 1;
+        while left < right {
+            let mid = left + (right - left + 1) / 2;
+            let decoded = self.decode_entry(self.restart_points[mid] as usize, &[], None)?;
+            if self.comparator.compare(&decoded.key, target_key) != std::cmp::Ordering::Greater {
+                left = mid;
+            } else {
+                right = mid - 1;
+            }
+        }
 
-            let shared_key_len = self.read_varint(&mut cursor)?;
-            let unshared_key_len = self.read_varint(&mut cursor)?;
-            let value_len = self.read_varint(&mut cursor)?;
+        Ok(self.restart_points[left] as usize)
+    }
 
-            if shared_key_len > last_key.len() as u32 {
-                return Err(Error::InvalidBlockFormat(
-                    "Shared key length exceeds previous key length in index block".to_string(),
-                ));
-            }
+    /// Decode a single index entry — the `(shared, unshared, value_len)`
+    /// varint triple, the key (via `last_key` prefix-sharing), and its value
+    /// (via [`Self::parse_index_value`], using `prev_handle` when
+    /// `self.value_format` delta-encodes handles) — starting at byte offset
+    /// `pos`. `prev_handle` must be `None` at a restart point (delta
+    /// encoding resets there, same as `last_key`) and `Some` of the
+    /// previously decoded handle otherwise. This is the one place entries
+    /// are decoded from: [`IndexBlockIter::next`]/[`IndexBlockIter::seek`]
+    /// call it to walk forward, [`Self::restart_for_key`] calls it to read a
+    /// restart's key during binary search, and [`Self::scan`] calls it to
+    /// walk the block defensively, so none of them can drift on the wire
+    /// format. Passing `last_key: &[]` decodes a restart-point entry —
+    /// restart points are defined to always have `shared_key_len == 0`, so
+    /// an empty `last_key` doubles as that validation.
+    fn decode_entry(
+        &self,
+        pos: usize,
+        last_key: &[u8],
+        prev_handle: Option<&BlockHandle>,
+    ) -> Result<DecodedEntry> {
+        let mut cursor = Cursor::new(&self.data);
+        cursor.set_position(pos as u64);
 
-            let mut key = Vec::new();
-            key.extend_from_slice(&last_key[..shared_key_len as usize]);
+        let shared_key_len = self.read_varint(&mut cursor)?;
+        let unshared_key_len = self.read_varint(&mut cursor)?;
+        let value_len = self.read_varint(&mut cursor)?;
 
-            if unshared_key_len > 0 {
-                let pos = cursor.position() as usize;
-                if pos + unshared_key_len as usize > self.data.len() {
-                    return Err(Error::InvalidBlockFormat(
-                        "Index key extends beyond block".to_string(),
-                    ));
-                }
-                key.extend_from_slice(&self.data[pos..pos + unshared_key_len as usize]);
-                cursor.set_position((pos + unshared_key_len as usize) as u64);
-            }
+        if shared_key_len > last_key.len() as u32 {
+            return Err(Error::InvalidBlockFormat(
+                "Shared key length exceeds previous key length in index block".to_string(),
+            ));
+        }
 
-            if value_len == 0 {
-                return Err(Error::InvalidBlockFormat(
-                    "Index entry must have value (block handle)".to_string(),
-                ));
-            }
+        let mut key = Vec::new();
+        key.extend_from_slice(&last_key[..shared_key_len as usize]);
 
-            let value_start = cursor.position() as usize;
-            if value_start + value_len as usize > self.data.len() {
+        let mut cur_pos = cursor.position() as usize;
+        if unshared_key_len > 0 {
+            if cur_pos + unshared_key_len as usize > self.data.len() {
                 return Err(Error::InvalidBlockFormat(
-                    "Index value extends beyond block".to_string(),
+                    "Index key extends beyond block".to_string(),
                 ));
             }
+            key.extend_from_slice(&self.data[cur_pos..cur_pos + unshared_key_len as usize]);
+            cur_pos += unshared_key_len as usize;
+        }
 
-            let value_data = &self.data[value_start..value_start + value_len as usize];
-            let block_handle = self.parse_block_handle(value_data)?;
-            cursor.set_position((value_start + value_len as usize) as u64);
-
-            last_key = key.clone();
-            entries.push(IndexEntry { key, block_handle });
-
-            if self.is_restart_point(entry_start as u32) {
-                last_key.clear();
-            }
+        if value_len == 0 {
+            return Err(Error::InvalidBlockFormat(
+                "Index entry must have value (block handle)".to_string(),
+            ));
+        }
+        if cur_pos + value_len as usize > self.data.len() {
+            return Err(Error::InvalidBlockFormat(
+                "Index value extends beyond block".to_string(),
+            ));
         }
 
-        Ok(entries)
+        let value_data = &self.data[cur_pos..cur_pos + value_len as usize];
+        let (first_key, handle) = self.parse_index_value(value_data, prev_handle)?;
+        cur_pos += value_len as usize;
+
+        Ok(DecodedEntry {
+            key,
+            handle,
+            next_pos: cur_pos,
+            shared_key_len,
+            first_key,
+        })
     }
 
-    fn parse_block_handle(&self, data: &[u8]) -> Result<BlockHandle> {
+    /// Decode an index entry's value bytes per `self.value_format`. In
+    /// [`IndexValueFormat::DeltaWithFirstKey`] mode, a length-prefixed
+    /// (varint32) first key comes first. The block handle itself is a full
+    /// `(offset, size)` varint64 pair in [`IndexValueFormat::Full`] mode, or
+    /// — in [`IndexValueFormat::Delta`]/[`IndexValueFormat::DeltaWithFirstKey`]
+    /// mode — just a varint64 `size` whenever `prev_handle` is `Some`, with
+    /// `offset` computed as `prev_handle.offset + prev_handle.size`; the
+    /// first entry of a restart interval has no previous handle to delta
+    /// against, so it still carries a full pair even under delta encoding.
+    fn parse_index_value(
+        &self,
+        data: &[u8],
+        prev_handle: Option<&BlockHandle>,
+    ) -> Result<(Option<Vec<u8>>, BlockHandle)> {
         let mut cursor = Cursor::new(data);
 
-        let offset = self.read_varint_from_slice(&mut cursor)?;
-        let size = self.read_varint_from_slice(&mut cursor)?;
+        let first_key = if self.value_format == IndexValueFormat::DeltaWithFirstKey {
+            let first_key_len = self.read_varint_from_slice(&mut cursor)? as usize;
+            let start = cursor.position() as usize;
+            if start + first_key_len > data.len() {
+                return Err(Error::InvalidBlockFormat(
+                    "Index entry's first key extends beyond its value".to_string(),
+                ));
+            }
+            let key = data[start..start + first_key_len].to_vec();
+            cursor.set_position((start + first_key_len) as u64);
+            Some(key)
+        } else {
+            None
+        };
+
+        let use_delta =
+            self.value_format != IndexValueFormat::Full && prev_handle.is_some();
 
-        Ok(BlockHandle {
-            offset: offset as u64,
-            size: size as u64,
-        })
+        let handle = if use_delta {
+            // Safe to unwrap: `use_delta` only holds when `prev_handle` is `Some`.
+            let prev = prev_handle.unwrap();
+            let size = self.read_varint64_from_slice(&mut cursor)?;
+            let offset = prev.offset.checked_add(prev.size).ok_or_else(|| {
+                Error::InvalidBlockFormat(
+                    "Delta-encoded index handle's implied offset overflowed".to_string(),
+                )
+            })?;
+            BlockHandle { offset, size }
+        } else {
+            // varint64, not varint32 — matches how
+            // `crate::block_builder::IndexBlockBuilder::add_index_entry`
+            // encodes a handle's offset and size, so a table larger than 4
+            // GiB still decodes correctly.
+            let offset = self.read_varint64_from_slice(&mut cursor)?;
+            let size = self.read_varint64_from_slice(&mut cursor)?;
+            BlockHandle { offset, size }
+        };
+
+        Ok((first_key, handle))
     }
 
     fn read_varint_from_slice(&self, cursor: &mut Cursor<&[u8]>) -> Result<u32> {
@@ -204,6 +608,36 @@ impl IndexBlock {
         Ok(result)
     }
 
+    fn read_varint64_from_slice(&self, cursor: &mut Cursor<&[u8]>) -> Result<u64> {
+        let mut result = 0u64;
+        let mut shift = 0;
+
+        loop {
+            let data = cursor.get_ref();
+            let pos = cursor.position() as usize;
+
+            if pos >= data.len() {
+                return Err(Error::InvalidVarint);
+            }
+
+            let byte = data[pos];
+            cursor.set_position(cursor.position() + 1);
+
+            result |= ((byte & 0x7F) as u64) << shift;
+
+            if (byte & 0x80) == 0 {
+                break;
+            }
+
+            shift += 7;
+            if shift >= 64 {
+                return Err(Error::InvalidVarint);
+            }
+        }
+
+        Ok(result)
+    }
+
     fn read_varint(&self, cursor: &mut Cursor<&Vec<u8>>) -> Result<u32> {
         let mut result = 0u32;
         let mut shift = 0;
@@ -235,28 +669,217 @@ impl IndexBlock {
         self.restart_points.contains(&offset)
     }
 
+    /// Find the block handle for the first entry whose key is `>=
+    /// target_key`, mirroring the classic LevelDB restart-based lookup: jump
+    /// to the nearest restart via [`IndexBlockIter::seek`] (restart-point
+    /// binary search), then take the first entry it yields. Falls back to
+    /// the last entry's handle when `target_key` exceeds every key in the
+    /// block, same as before this was restart-based. O(log R + interval)
+    /// instead of O(n), and no longer materializes every entry into a `Vec`
+    /// first.
     pub fn find_block_for_key(&self, target_key: &[u8]) -> Result<Option<BlockHandle>> {
-        let entries = self.get_entries()?;
+        if self.restart_points.is_empty() {
+            return Ok(None);
+        }
 
-        for entry in entries.iter() {
-            if entry.key.as_slice() >= target_key {
-                return Ok(Some(entry.block_handle.clone()));
-            }
+        let mut iter = self.iter();
+        iter.seek(target_key)?;
+        if let Some((_, handle)) = iter.next()? {
+            return Ok(Some(handle.clone()));
         }
 
-        if let Some(last_entry) = entries.last() {
-            Ok(Some(last_entry.block_handle.clone()))
-        } else {
-            Ok(None)
+        // `target_key` exceeds every key in the block: `seek` left the
+        // scanned-but-unmatched handle (the block's last entry) behind for
+        // exactly this fallback.
+        Ok(iter.current_handle.clone())
+    }
+
+    /// Like [`Self::find_block_for_key`], but for an exact-match lookup
+    /// rather than a range-scan seek: if the candidate block was decoded
+    /// with its [`IndexEntry::first_key`] (i.e. [`IndexValueFormat::DeltaWithFirstKey`])
+    /// and `target_key` sorts before it, the block provably can't contain
+    /// `target_key` and `None` is returned without the caller reading it.
+    /// [`Self::find_block_for_key`] can't do this pruning itself — a
+    /// range-scan seek still needs the block even when `target_key` isn't
+    /// in it, since the block's first entry is the correct landing spot.
+    pub fn find_block_for_exact_key(&self, target_key: &[u8]) -> Result<Option<BlockHandle>> {
+        if self.restart_points.is_empty() {
+            return Ok(None);
         }
+
+        let mut iter = self.iter();
+        iter.seek(target_key)?;
+        let Some((_, handle)) = iter.next()? else {
+            return Ok(None);
+        };
+        let handle = handle.clone();
+
+        if let Some(first_key) = &iter.current_first_key {
+            if self.comparator.compare(target_key, first_key) == std::cmp::Ordering::Less {
+                return Ok(None);
+            }
+        }
+
+        Ok(Some(handle))
     }
 
     pub fn get_all_block_handles(&self) -> Result<Vec<BlockHandle>> {
-        let entries = self.get_entries()?;
-        Ok(entries
-            .into_iter()
-            .map(|entry| entry.block_handle)
-            .collect())
+        let mut handles = Vec::new();
+        let mut iter = self.iter();
+        while let Some((_, handle)) = iter.next()? {
+            handles.push(handle.clone());
+        }
+        Ok(handles)
+    }
+
+    /// Walk the block defensively and report what's wrong with it, instead
+    /// of either erroring out on the first malformed entry or silently
+    /// falling back to [`Self::with_comparator`]'s fake single-entry format.
+    /// Useful for tooling that needs to diagnose a partially damaged SST
+    /// without aborting on the first corruption found. `file_len`, if
+    /// known, flags block handles that point outside the file; pass `None`
+    /// to skip that check.
+    ///
+    /// On a decode failure mid-scan, resyncs to the next restart point past
+    /// the failure rather than giving up on the rest of the block, so one
+    /// bad entry doesn't hide every other finding.
+    pub fn scan(&self, file_len: Option<u64>) -> ScanStats {
+        let mut stats = ScanStats::default();
+
+        for &restart_pos in &self.restart_points {
+            match self.decode_entry(restart_pos as usize, &[], None) {
+                Ok(decoded) if decoded.shared_key_len == 0 => {}
+                _ => stats.record_bad_restart_boundary(restart_pos as usize),
+            }
+        }
+
+        let mut pos = self.initial_pos();
+        let mut last_key: Option<Vec<u8>> = None;
+        let mut prev_handle: Option<BlockHandle> = None;
+
+        while pos < self.restart_offset {
+            let is_restart = self.is_restart_point(pos as u32);
+            let decode_last_key: &[u8] = if is_restart {
+                &[]
+            } else {
+                last_key.as_deref().unwrap_or(&[])
+            };
+            let decode_prev_handle = if is_restart { None } else { prev_handle.as_ref() };
+
+            match self.decode_entry(pos, decode_last_key, decode_prev_handle) {
+                Ok(decoded) => {
+                    stats.decodable_entries += 1;
+
+                    if decoded.shared_key_len == 0 && !is_restart {
+                        stats.record_inconsistent_prefix_sharing(pos);
+                    }
+
+                    if let Some(prev) = &last_key {
+                        if self.comparator.compare(&decoded.key, prev) == std::cmp::Ordering::Less
+                        {
+                            stats.record_non_monotonic_key(pos);
+                        }
+                    }
+
+                    let in_range = match decoded.handle.offset.checked_add(decoded.handle.size) {
+                        Some(end) => match file_len {
+                            Some(len) => end <= len,
+                            None => true,
+                        },
+                        None => false,
+                    };
+                    if !in_range {
+                        stats.record_out_of_range_handle(pos);
+                    }
+
+                    last_key = Some(decoded.key);
+                    prev_handle = Some(decoded.handle);
+                    pos = decoded.next_pos;
+                }
+                Err(_) => {
+                    // Resync to the next restart point past this one, so a
+                    // single malformed entry doesn't stop the scan from
+                    // reporting everything else in the block.
+                    match self
+                        .restart_points
+                        .iter()
+                        .find(|&&r| r as usize > pos)
+                    {
+                        Some(&next_restart) => {
+                            pos = next_restart as usize;
+                            last_key = None;
+                            prev_handle = None;
+                        }
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+/// A bounded sample of byte offsets, used by [`ScanStats`] so a badly
+/// corrupted block with thousands of offending entries doesn't blow up the
+/// report's size — callers that want the full count still have the
+/// corresponding counter field.
+const MAX_SAMPLED_OFFSETS: usize = 16;
+
+/// Corruption/consistency report from [`IndexBlock::scan`]. All counters
+/// reflect the full block; the `Vec` fields are capped at
+/// [`MAX_SAMPLED_OFFSETS`] offending offsets each, for tooling that wants a
+/// few concrete examples without paying for an unbounded list.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScanStats {
+    /// Number of entries that decoded successfully.
+    pub decodable_entries: usize,
+    /// Number of entries with `shared_key_len == 0` at a non-restart
+    /// position — a key claiming no sharing with its predecessor despite
+    /// not being a declared restart boundary.
+    pub inconsistent_prefix_sharing: usize,
+    pub inconsistent_prefix_sharing_offsets: Vec<usize>,
+    /// Number of restart points whose entry failed to decode, or decoded
+    /// with `shared_key_len != 0` (restart entries must stand alone).
+    pub bad_restart_boundaries: usize,
+    pub bad_restart_boundary_offsets: Vec<usize>,
+    /// Number of block handles whose `offset + size` overflows a `u64`, or
+    /// (when a file length was supplied to [`IndexBlock::scan`]) falls
+    /// outside it.
+    pub out_of_range_handles: usize,
+    pub out_of_range_handle_offsets: Vec<usize>,
+    /// Number of entries whose key sorts before the previous entry's key.
+    pub non_monotonic_keys: usize,
+    pub non_monotonic_key_offsets: Vec<usize>,
+}
+
+impl ScanStats {
+    fn record_bad_restart_boundary(&mut self, offset: usize) {
+        self.bad_restart_boundaries += 1;
+        if self.bad_restart_boundary_offsets.len() < MAX_SAMPLED_OFFSETS {
+            self.bad_restart_boundary_offsets.push(offset);
+        }
+    }
+
+    fn record_inconsistent_prefix_sharing(&mut self, offset: usize) {
+        self.inconsistent_prefix_sharing += 1;
+        if self.inconsistent_prefix_sharing_offsets.len() < MAX_SAMPLED_OFFSETS {
+            self.inconsistent_prefix_sharing_offsets.push(offset);
+        }
+    }
+
+    fn record_out_of_range_handle(&mut self, offset: usize) {
+        self.out_of_range_handles += 1;
+        if self.out_of_range_handle_offsets.len() < MAX_SAMPLED_OFFSETS {
+            self.out_of_range_handle_offsets.push(offset);
+        }
+    }
+
+    fn record_non_monotonic_key(&mut self, offset: usize) {
+        self.non_monotonic_keys += 1;
+        if self.non_monotonic_key_offsets.len() < MAX_SAMPLED_OFFSETS {
+            self.non_monotonic_key_offsets.push(offset);
+        }
     }
 }
 
@@ -264,6 +887,7 @@ impl IndexBlock {
 mod tests {
     use super::*;
     use crate::block_builder::IndexBlockBuilder;
+    use crate::comparator::InternalKeyComparator;
     use crate::error::Result;
     use crate::types::{ChecksumType, CompressionType};
 
@@ -279,7 +903,7 @@ mod tests {
         builder.add_index_entry(key1, &handle1);
         let block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
 
-        let index_block = IndexBlock::new(&block_data, CompressionType::None)?;
+        let index_block = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::CRC32c)?;
         let entries = index_block.get_entries()?;
 
         assert_eq!(entries.len(), 1);
@@ -289,6 +913,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_roundtrip_index_block_handle_beyond_4gib() -> Result<()> {
+        let key1 = b"key001";
+        let handle1 = BlockHandle {
+            offset: (u32::MAX as u64) + 1_000_000,
+            size: (u32::MAX as u64) * 2,
+        };
+
+        let mut builder = IndexBlockBuilder::new(16);
+        builder.add_index_entry(key1, &handle1);
+        let block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let index_block = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::CRC32c)?;
+        let entries = index_block.get_entries()?;
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].block_handle.offset, handle1.offset);
+        assert_eq!(entries[0].block_handle.size, handle1.size);
+        Ok(())
+    }
+
     #[test]
     fn test_roundtrip_find_block_for_key() -> Result<()> {
         let key1 = b"key001";
@@ -307,7 +952,7 @@ mod tests {
         builder.add_index_entry(key2, &handle2);
         let block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
 
-        let index_block = IndexBlock::new(&block_data, CompressionType::None)?;
+        let index_block = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::CRC32c)?;
 
         let result = index_block.find_block_for_key(b"key000")?;
         assert!(result.is_some());
@@ -322,4 +967,535 @@ mod tests {
         assert_eq!(result.unwrap().offset, handle2.offset);
         Ok(())
     }
+
+    #[test]
+    fn test_find_block_for_key_binary_search_across_many_restarts() -> Result<()> {
+        // restart_interval=1 means every entry is its own restart point, so
+        // 20 entries exercises several rounds of binary search rather than
+        // just the single comparison a 1-2-restart block would.
+        let mut builder = IndexBlockBuilder::new(1);
+        let mut handles = Vec::new();
+        for i in 0..20u64 {
+            let key = format!("key{:03}", i * 10);
+            let handle = BlockHandle {
+                offset: i * 1000,
+                size: 500,
+            };
+            builder.add_index_entry(key.as_bytes(), &handle);
+            handles.push(handle);
+        }
+        let block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let index_block = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::CRC32c)?;
+
+        // Exact matches land on the entry itself.
+        let result = index_block.find_block_for_key(b"key090")?;
+        assert_eq!(result.unwrap().offset, handles[9].offset);
+
+        // A key between two restarts resolves to the next entry >= it.
+        let result = index_block.find_block_for_key(b"key095")?;
+        assert_eq!(result.unwrap().offset, handles[10].offset);
+
+        // A key before the first entry resolves to the first entry.
+        let result = index_block.find_block_for_key(b"key000")?;
+        assert_eq!(result.unwrap().offset, handles[0].offset);
+
+        // A key past the last entry falls back to the last entry's handle.
+        let result = index_block.find_block_for_key(b"zzzzzz")?;
+        assert_eq!(result.unwrap().offset, handles[19].offset);
+
+        Ok(())
+    }
+
+    fn internal_key(user_key: &[u8], sequence: u64, value_type: u8) -> Vec<u8> {
+        let mut key = user_key.to_vec();
+        let trailer = (sequence << 8) | value_type as u64;
+        key.extend_from_slice(&trailer.to_le_bytes());
+        key
+    }
+
+    #[test]
+    fn test_with_comparator_orders_internal_keys_by_user_key_then_sequence() -> Result<()> {
+        // Internal keys sort by user key ascending, then sequence number
+        // descending — so in insertion order "a"@2 sorts before "a"@1, which
+        // sorts before "b"@1, even though the raw bytes of "a"@2 and "a"@1
+        // only differ in the trailer.
+        let key_a2 = internal_key(b"a", 2, 1);
+        let key_a1 = internal_key(b"a", 1, 1);
+        let key_b1 = internal_key(b"b", 1, 1);
+        let handle_a2 = BlockHandle {
+            offset: 0,
+            size: 10,
+        };
+        let handle_a1 = BlockHandle {
+            offset: 10,
+            size: 10,
+        };
+        let handle_b1 = BlockHandle {
+            offset: 20,
+            size: 10,
+        };
+
+        let mut builder = IndexBlockBuilder::new(1);
+        builder.add_index_entry(&key_a2, &handle_a2);
+        builder.add_index_entry(&key_a1, &handle_a1);
+        builder.add_index_entry(&key_b1, &handle_b1);
+        let block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let index_block = IndexBlock::with_comparator(
+            &block_data,
+            CompressionType::None,
+            std::sync::Arc::new(InternalKeyComparator),
+        )?;
+
+        // Looking up "a"@2 exactly lands on its own entry.
+        let result = index_block.find_block_for_key(&key_a2)?;
+        assert_eq!(result.unwrap().offset, handle_a2.offset);
+
+        // A lookup key for "a" older than every stored sequence for "a"
+        // sorts *after* both "a" entries under descending-sequence ordering,
+        // so the first entry >= it is "b"@1 — not the raw-byte-order
+        // neighbor a naive bytewise comparator would pick.
+        let probe = internal_key(b"a", 0, 1);
+        let result = index_block.find_block_for_key(&probe)?;
+        assert_eq!(result.unwrap().offset, handle_b1.offset);
+
+        let result = index_block.find_block_for_key(&key_b1)?;
+        assert_eq!(result.unwrap().offset, handle_b1.offset);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_detects_a_flipped_trailer_byte() -> Result<()> {
+        let handle = BlockHandle {
+            offset: 100,
+            size: 200,
+        };
+        let mut builder = IndexBlockBuilder::new(16);
+        builder.add_index_entry(b"key001", &handle);
+        let mut block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let last = block_data.len() - 1;
+        block_data[last] ^= 0xff;
+
+        let result = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::CRC32c);
+        assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_detects_corruption_in_the_block_body_too() -> Result<()> {
+        let handle = BlockHandle {
+            offset: 100,
+            size: 200,
+        };
+        let mut builder = IndexBlockBuilder::new(16);
+        builder.add_index_entry(b"key001", &handle);
+        let mut block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        block_data[0] ^= 0xff;
+
+        let result = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::CRC32c);
+        assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_unchecked_parses_a_corrupted_block_without_complaint() -> Result<()> {
+        // `new_unchecked` preserves the lenient behavior `new` had before
+        // trailer verification was added: a corrupted trailer doesn't stop
+        // it from parsing (possibly wrong) entries.
+        let handle = BlockHandle {
+            offset: 100,
+            size: 200,
+        };
+        let mut builder = IndexBlockBuilder::new(16);
+        builder.add_index_entry(b"key001", &handle);
+        let mut block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let last = block_data.len() - 1;
+        block_data[last] ^= 0xff;
+
+        let index_block = IndexBlock::new_unchecked(&block_data, CompressionType::None)?;
+        assert_eq!(index_block.get_entries()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_with_checksum_type_none_skips_verification() -> Result<()> {
+        let handle = BlockHandle {
+            offset: 100,
+            size: 200,
+        };
+        let mut builder = IndexBlockBuilder::new(16);
+        builder.add_index_entry(b"key001", &handle);
+        let mut block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let last = block_data.len() - 1;
+        block_data[last] ^= 0xff;
+
+        let index_block = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::None)?;
+        assert_eq!(index_block.get_entries()?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_reports_a_clean_block_as_clean() -> Result<()> {
+        let mut builder = IndexBlockBuilder::new(1);
+        for i in 0..5u64 {
+            let key = format!("key{:03}", i * 10);
+            let handle = BlockHandle {
+                offset: i * 1000,
+                size: 500,
+            };
+            builder.add_index_entry(key.as_bytes(), &handle);
+        }
+        let block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let index_block = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::CRC32c)?;
+
+        let stats = index_block.scan(None);
+        assert_eq!(stats.decodable_entries, 5);
+        assert_eq!(stats.inconsistent_prefix_sharing, 0);
+        assert_eq!(stats.bad_restart_boundaries, 0);
+        assert_eq!(stats.out_of_range_handles, 0);
+        assert_eq!(stats.non_monotonic_keys, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_flags_out_of_range_block_handles() -> Result<()> {
+        let mut builder = IndexBlockBuilder::new(1);
+        builder.add_index_entry(
+            b"key001",
+            &BlockHandle {
+                offset: 100,
+                size: 200,
+            },
+        );
+        builder.add_index_entry(
+            b"key002",
+            &BlockHandle {
+                offset: 1_000_000,
+                size: 200,
+            },
+        );
+        let block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let index_block = IndexBlock::new(&block_data, CompressionType::None, ChecksumType::CRC32c)?;
+
+        // A file length smaller than the second handle's end flags only it.
+        let stats = index_block.scan(Some(1000));
+        assert_eq!(stats.decodable_entries, 2);
+        assert_eq!(stats.out_of_range_handles, 1);
+
+        let stats = index_block.scan(None);
+        assert_eq!(stats.out_of_range_handles, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_flags_a_bad_restart_boundary() -> Result<()> {
+        let mut builder = IndexBlockBuilder::new(1);
+        for i in 0..3u64 {
+            let key = format!("key{:03}", i * 10);
+            let handle = BlockHandle {
+                offset: i * 1000,
+                size: 500,
+            };
+            builder.add_index_entry(key.as_bytes(), &handle);
+        }
+        let mut block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        // Overwrite the first restart point with a bogus offset that doesn't
+        // land on any real entry boundary, to force a restart-entry decode
+        // failure without corrupting the rest of the block.
+        let restart_array_start = block_data.len() - 5 - 4 - 4 * 3;
+        LittleEndian::write_u32(&mut block_data[restart_array_start..], 9_999);
+
+        let index_block = IndexBlock::new_unchecked(&block_data, CompressionType::None)?;
+        let stats = index_block.scan(None);
+        assert!(stats.bad_restart_boundaries >= 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_resyncs_past_a_decode_failure() -> Result<()> {
+        // restart_interval=1 means every entry is a restart point, so
+        // corrupting the header byte at the third entry's restart offset
+        // (stored in the restart array, which we can read directly out of
+        // `block_data`) forces a decode failure for exactly that entry,
+        // without disturbing entries 0, 1, or 3.
+        let mut builder = IndexBlockBuilder::new(1);
+        for i in 0..4u64 {
+            let key = format!("key{:03}", i * 10);
+            let handle = BlockHandle {
+                offset: i * 1000,
+                size: 500,
+            };
+            builder.add_index_entry(key.as_bytes(), &handle);
+        }
+        let mut block_data = builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+
+        let num_restarts = 4usize;
+        let restart_array_start = block_data.len() - 5 - 4 - num_restarts * 4;
+        let third_restart_offset =
+            LittleEndian::read_u32(&block_data[restart_array_start + 2 * 4..]) as usize;
+
+        // A 0xff shared_key_len byte carries into the following unshared
+        // length byte, decoding to a shared length far longer than the empty
+        // previous key at a restart point, so this entry fails to decode
+        // while entries 0, 1, and 3 remain untouched.
+        block_data[third_restart_offset] = 0xff;
+
+        let index_block = IndexBlock::new_unchecked(&block_data, CompressionType::None)?;
+        let stats = index_block.scan(None);
+        assert!(stats.decodable_entries >= 1);
+        assert!(stats.decodable_entries < 4);
+        Ok(())
+    }
+
+    fn encode_varint32(mut value: u32) -> Vec<u8> {
+        let mut out = Vec::new();
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+        out
+    }
+
+    fn encode_varint64(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+        out
+    }
+
+    /// Hand-encodes a single index entry's header + key, for tests that need
+    /// value bytes [`crate::block_builder::IndexBlockBuilder`] can't produce
+    /// (it only ever writes [`IndexValueFormat::Full`] values).
+    fn encode_entry_header(shared: u32, unshared: u32, value_len: u32, unshared_key: &[u8]) -> Vec<u8> {
+        let mut out = encode_varint32(shared);
+        out.extend(encode_varint32(unshared));
+        out.extend(encode_varint32(value_len));
+        out.extend_from_slice(unshared_key);
+        out
+    }
+
+    /// Hand-assembles a raw (uncompressed, single-restart-array) index block
+    /// from already-encoded entry bytes, appending a dummy 5-byte trailer —
+    /// `IndexBlock::with_format` strips exactly 5 trailer bytes regardless
+    /// of their content for `CompressionType::None`.
+    fn assemble_raw_block(entries: &[u8], restart_points: &[u32]) -> Vec<u8> {
+        let mut data = entries.to_vec();
+        for &restart in restart_points {
+            data.extend_from_slice(&restart.to_le_bytes());
+        }
+        data.extend_from_slice(&(restart_points.len() as u32).to_le_bytes());
+        data.extend_from_slice(&[0u8; 5]);
+        data
+    }
+
+    #[test]
+    fn test_with_format_decodes_delta_encoded_handles() -> Result<()> {
+        // Entry 0 is the block's only restart point, so it carries a full
+        // (offset, size) handle; entry 1 stores only a size, with its offset
+        // implied by entry 0's offset + size.
+        let key0 = b"key000";
+        let handle0 = BlockHandle {
+            offset: 1000,
+            size: 200,
+        };
+        let value0 = [encode_varint64(handle0.offset), encode_varint64(handle0.size)].concat();
+        let entry0 = encode_entry_header(0, key0.len() as u32, value0.len() as u32, key0)
+            .into_iter()
+            .chain(value0)
+            .collect::<Vec<u8>>();
+
+        let key1 = b"key010";
+        let size1 = 300u64;
+        let value1 = encode_varint64(size1);
+        let entry1 = encode_entry_header(0, key1.len() as u32, value1.len() as u32, key1)
+            .into_iter()
+            .chain(value1)
+            .collect::<Vec<u8>>();
+
+        // Only entry 0 is a restart point, so entry 1 decodes against
+        // entry 0's handle via delta encoding rather than being treated as
+        // another standalone, full-handle restart.
+        let entries = [entry0, entry1].concat();
+        let block_data = assemble_raw_block(&entries, &[0]);
+
+        let index_block = IndexBlock::with_format(
+            &block_data,
+            CompressionType::None,
+            Arc::new(BytewiseComparator),
+            IndexValueFormat::Delta,
+        )?;
+        let entries = index_block.get_entries()?;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].key, key0);
+        assert_eq!(entries[0].block_handle, handle0);
+        assert_eq!(entries[1].key, key1);
+        assert_eq!(
+            entries[1].block_handle,
+            BlockHandle {
+                offset: handle0.offset + handle0.size,
+                size: size1,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_format_decodes_delta_with_first_key() -> Result<()> {
+        // Same shape as the delta-only test, but every value is prefixed
+        // with a length-prefixed first key for the data block it points to.
+        let key0 = b"key000";
+        let handle0 = BlockHandle {
+            offset: 2000,
+            size: 400,
+        };
+        let first_key0 = b"aaa";
+        let value0 = [
+            encode_varint32(first_key0.len() as u32),
+            first_key0.to_vec(),
+            encode_varint64(handle0.offset),
+            encode_varint64(handle0.size),
+        ]
+        .concat();
+        let entry0 = encode_entry_header(0, key0.len() as u32, value0.len() as u32, key0)
+            .into_iter()
+            .chain(value0)
+            .collect::<Vec<u8>>();
+
+        let key1 = b"key010";
+        let first_key1 = b"mmm";
+        let size1 = 150u64;
+        let value1 = [
+            encode_varint32(first_key1.len() as u32),
+            first_key1.to_vec(),
+            encode_varint64(size1),
+        ]
+        .concat();
+        let entry1 = encode_entry_header(0, key1.len() as u32, value1.len() as u32, key1)
+            .into_iter()
+            .chain(value1)
+            .collect::<Vec<u8>>();
+
+        // Only entry 0 is a restart point, so entry 1 decodes against
+        // entry 0's handle via delta encoding rather than being treated as
+        // another standalone, full-handle restart.
+        let entries = [entry0, entry1].concat();
+        let block_data = assemble_raw_block(&entries, &[0]);
+
+        let index_block = IndexBlock::with_format(
+            &block_data,
+            CompressionType::None,
+            Arc::new(BytewiseComparator),
+            IndexValueFormat::DeltaWithFirstKey,
+        )?;
+        let entries = index_block.get_entries()?;
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].first_key.as_deref(), Some(first_key0.as_slice()));
+        assert_eq!(entries[0].block_handle, handle0);
+        assert_eq!(entries[1].first_key.as_deref(), Some(first_key1.as_slice()));
+        assert_eq!(
+            entries[1].block_handle,
+            BlockHandle {
+                offset: handle0.offset + handle0.size,
+                size: size1,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_block_for_exact_key_prunes_using_first_key() -> Result<()> {
+        // entry0 covers [aaa, key000], entry1 covers [key005, key010].
+        let key0 = b"key000";
+        let handle0 = BlockHandle {
+            offset: 2000,
+            size: 400,
+        };
+        let first_key0 = b"aaa";
+        let value0 = [
+            encode_varint32(first_key0.len() as u32),
+            first_key0.to_vec(),
+            encode_varint64(handle0.offset),
+            encode_varint64(handle0.size),
+        ]
+        .concat();
+        let entry0 = encode_entry_header(0, key0.len() as u32, value0.len() as u32, key0)
+            .into_iter()
+            .chain(value0)
+            .collect::<Vec<u8>>();
+
+        let key1 = b"key010";
+        let first_key1 = b"key005";
+        let handle1 = BlockHandle {
+            offset: handle0.offset + handle0.size,
+            size: 150,
+        };
+        let value1 = [
+            encode_varint32(first_key1.len() as u32),
+            first_key1.to_vec(),
+            encode_varint64(handle1.size),
+        ]
+        .concat();
+        let entry1 = encode_entry_header(0, key1.len() as u32, value1.len() as u32, key1)
+            .into_iter()
+            .chain(value1)
+            .collect::<Vec<u8>>();
+
+        let entries = [entry0, entry1].concat();
+        let block_data = assemble_raw_block(&entries, &[0]);
+
+        let index_block = IndexBlock::with_format(
+            &block_data,
+            CompressionType::None,
+            Arc::new(BytewiseComparator),
+            IndexValueFormat::DeltaWithFirstKey,
+        )?;
+
+        // "key003" lands on entry1 via the restart search (it's <= "key010"
+        // and > "key000"), but entry1's first key ("key005") proves the
+        // block can't contain it.
+        assert_eq!(index_block.find_block_for_exact_key(b"key003")?, None);
+
+        // "key007" is within entry1's actual key range.
+        assert_eq!(
+            index_block.find_block_for_exact_key(b"key007")?,
+            Some(handle1.clone())
+        );
+
+        // Unaffected by pruning: plain [`Self::find_block_for_key`] still
+        // returns entry1's handle for "key003", since a range-scan seek
+        // needs to land there regardless of whether it contains the key.
+        assert_eq!(
+            index_block.find_block_for_key(b"key003")?,
+            Some(handle1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_format_version_picks_delta_at_version_4() {
+        assert_eq!(
+            IndexValueFormat::from_format_version(3),
+            IndexValueFormat::Full
+        );
+        assert_eq!(
+            IndexValueFormat::from_format_version(4),
+            IndexValueFormat::Delta
+        );
+        assert_eq!(
+            IndexValueFormat::from_format_version(6),
+            IndexValueFormat::Delta
+        );
+    }
 }
@@ -1,13 +1,20 @@
+use crate::block_source::BlockSource;
 use crate::data_block::DataBlockReader;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::index_block::IndexBlock;
+use crate::merge::MergeOperator;
+use crate::sst_file_writer::EntryType;
 use crate::sst_reader::SstReader;
 use crate::types::CompressionType;
+use std::ops::Bound;
 
 pub trait SstIterator {
     fn seek_to_first(&mut self) -> Result<()>;
     fn seek_to_last(&mut self) -> Result<()>;
     fn seek(&mut self, key: &[u8]) -> Result<()>;
+    /// Land on the largest key `<= key`, or invalidate the iterator if no
+    /// such key exists. The backward counterpart to [`Self::seek`].
+    fn seek_for_prev(&mut self, key: &[u8]) -> Result<()>;
     fn next(&mut self) -> Result<bool>;
     fn prev(&mut self) -> Result<bool>;
     fn valid(&self) -> bool;
@@ -15,21 +22,20 @@ pub trait SstIterator {
     fn value(&self) -> Option<&[u8]>;
 }
 
-pub struct SstTableIterator {
-    sst_reader: SstReader,
+pub struct SstTableIterator<S: BlockSource> {
+    sst_reader: SstReader<S>,
     index_block: IndexBlock,
     current_data_block: Option<DataBlockReader>,
     current_block_index: usize,
     all_block_handles: Vec<crate::block_handle::BlockHandle>,
-    compression_type: CompressionType,
     valid: bool,
 }
 
-impl SstTableIterator {
-    pub fn new(mut sst_reader: SstReader, compression_type: CompressionType) -> Result<Self> {
+impl<S: BlockSource> SstTableIterator<S> {
+    pub fn new(mut sst_reader: SstReader<S>) -> Result<Self> {
         let footer = sst_reader.get_footer();
-        let index_data = sst_reader.read_block(footer.index_handle.clone())?;
-        let index_block = IndexBlock::new(&index_data, CompressionType::None)?;
+        let index_data = sst_reader.read_index_block_bytes(footer.index_handle.clone())?;
+        let index_block = IndexBlock::new_unchecked(&index_data, CompressionType::None)?;
         let all_block_handles = index_block.get_all_block_handles()?;
 
         Ok(SstTableIterator {
@@ -38,7 +44,6 @@ impl SstTableIterator {
             current_data_block: None,
             current_block_index: 0,
             all_block_handles,
-            compression_type,
             valid: false,
         })
     }
@@ -51,9 +56,7 @@ impl SstTableIterator {
         }
 
         let block_handle = self.all_block_handles[block_index].clone();
-        let data_block_reader = self
-            .sst_reader
-            .read_data_block_reader(block_handle, self.compression_type)?;
+        let data_block_reader = self.sst_reader.read_data_block_reader(block_handle)?;
 
         self.current_data_block = Some(data_block_reader);
         self.current_block_index = block_index;
@@ -70,9 +73,28 @@ impl SstTableIterator {
     pub fn block_count(&self) -> usize {
         self.all_block_handles.len()
     }
+
+    /// Whether `target_key` could be present, consulting (in order) the
+    /// index block's per-block first key and the table's Bloom filter
+    /// block, for the data block the index says it would land in. `false`
+    /// when the index has no candidate block at all, the index's first key
+    /// for that block proves `target_key` sorts before everything it holds
+    /// (see [`IndexBlock::find_block_for_exact_key`]), or the filter
+    /// definitely rules the key out — all three are the same "don't bother
+    /// reading a data block" signal [`SstEntryIterator::find`] wants.
+    pub(crate) fn may_contain_key(&mut self, target_key: &[u8]) -> Result<bool> {
+        match self.index_block.find_block_for_exact_key(target_key)? {
+            Some(handle) => self.sst_reader.filter_may_contain(handle.offset, target_key),
+            None => Ok(false),
+        }
+    }
+
+    pub(crate) fn read_options(&self) -> &crate::types::ReadOptions {
+        self.sst_reader.read_options()
+    }
 }
 
-impl SstIterator for SstTableIterator {
+impl<S: BlockSource> SstIterator for SstTableIterator<S> {
     fn seek_to_first(&mut self) -> Result<()> {
         if self.all_block_handles.is_empty() {
             self.valid = false;
@@ -101,17 +123,8 @@ impl SstIterator for SstTableIterator {
         self.load_data_block(last_block_index)?;
 
         if let Some(ref mut data_block) = self.current_data_block {
-            while data_block.next().is_some() {}
-            self.valid = false;
-
-            let entries_len = data_block.entries().len();
-            if entries_len > 0 {
-                data_block.seek_to_first();
-                for _ in 1..entries_len {
-                    data_block.next();
-                }
-                self.valid = data_block.valid();
-            }
+            data_block.seek_to_last();
+            self.valid = data_block.valid();
         } else {
             self.valid = false;
         }
@@ -145,6 +158,50 @@ impl SstIterator for SstTableIterator {
         Ok(())
     }
 
+    fn seek_for_prev(&mut self, target_key: &[u8]) -> Result<()> {
+        let block_handle = self.index_block.find_block_for_key(target_key)?;
+
+        let Some(handle) = block_handle else {
+            self.valid = false;
+            return Ok(());
+        };
+
+        let Some(block_index) = self
+            .all_block_handles
+            .iter()
+            .position(|h| h.offset == handle.offset && h.size == handle.size)
+        else {
+            self.valid = false;
+            return Ok(());
+        };
+
+        self.load_data_block(block_index)?;
+        if let Some(ref mut data_block) = self.current_data_block {
+            if data_block.seek_for_prev(target_key) {
+                self.valid = true;
+                return Ok(());
+            }
+        }
+
+        // Every key in this block is greater than `target_key` — the
+        // largest key `<= target_key`, if any, is the previous block's
+        // last entry.
+        if block_index == 0 {
+            self.valid = false;
+            return Ok(());
+        }
+
+        self.load_data_block(block_index - 1)?;
+        if let Some(ref mut data_block) = self.current_data_block {
+            data_block.seek_to_last();
+            self.valid = data_block.valid();
+        } else {
+            self.valid = false;
+        }
+
+        Ok(())
+    }
+
     fn next(&mut self) -> Result<bool> {
         if !self.valid {
             return Ok(false);
@@ -176,24 +233,24 @@ impl SstIterator for SstTableIterator {
             return Ok(false);
         }
 
-        if self.current_block_index > 0 {
-            let prev_block_index = self.current_block_index - 1;
-            self.load_data_block(prev_block_index)?;
+        if let Some(ref mut data_block) = self.current_data_block {
+            if data_block.prev().is_some() {
+                return Ok(true);
+            }
+        }
 
-            if let Some(ref mut data_block) = self.current_data_block {
-                data_block.seek_to_first();
-                while data_block.next().is_some() {}
+        if self.current_block_index == 0 {
+            self.valid = false;
+            return Ok(false);
+        }
 
-                let entries_len = data_block.entries().len();
-                if entries_len > 0 {
-                    data_block.seek_to_first();
-                    for _ in 1..entries_len {
-                        data_block.next();
-                    }
-                    self.valid = data_block.valid();
-                    return Ok(self.valid);
-                }
-            }
+        let prev_block_index = self.current_block_index - 1;
+        self.load_data_block(prev_block_index)?;
+
+        if let Some(ref mut data_block) = self.current_data_block {
+            data_block.seek_to_last();
+            self.valid = data_block.valid();
+            return Ok(self.valid);
         }
 
         self.valid = false;
@@ -221,17 +278,155 @@ impl SstIterator for SstTableIterator {
     }
 }
 
-pub struct SstEntryIterator {
-    iterator: SstTableIterator,
+/// A key-range-scoped view over an [`SstTableIterator`]. [`Self::seek_to_first`]
+/// jumps straight to the first in-range block via [`SstTableIterator::seek`]
+/// (which itself uses the index to avoid starting at block 0), and
+/// [`Self::valid`] goes false as soon as iteration crosses `upper`.
+///
+/// Only forward iteration (`seek_to_first`/`next`) is scoped to the range;
+/// reach for the underlying [`SstTableIterator`] directly if a bounded
+/// reverse scan is ever needed.
+pub struct SstRangeIterator<S: BlockSource> {
+    iterator: SstTableIterator<S>,
+    lower: Bound<Vec<u8>>,
+    upper: Bound<Vec<u8>>,
+    valid: bool,
 }
 
-impl SstEntryIterator {
-    pub fn new(sst_reader: SstReader, compression_type: CompressionType) -> Result<Self> {
-        let iterator = SstTableIterator::new(sst_reader, compression_type)?;
+impl<S: BlockSource> SstRangeIterator<S> {
+    pub fn new(iterator: SstTableIterator<S>, lower: Bound<Vec<u8>>, upper: Bound<Vec<u8>>) -> Self {
+        SstRangeIterator {
+            iterator,
+            lower,
+            upper,
+            valid: false,
+        }
+    }
+
+    /// Every key starting with `prefix`. The upper bound is derived by
+    /// incrementing `prefix`'s last non-`0xFF` byte (dropping any trailing
+    /// `0xFF` bytes first) — or left `Unbounded` if `prefix` is all `0xFF`,
+    /// since no key could sort past it anyway.
+    pub fn prefix(iterator: SstTableIterator<S>, prefix: &[u8]) -> Self {
+        let lower = Bound::Included(prefix.to_vec());
+        let upper = match increment_prefix(prefix) {
+            Some(bound) => Bound::Excluded(bound),
+            None => Bound::Unbounded,
+        };
+        SstRangeIterator {
+            iterator,
+            lower,
+            upper,
+            valid: false,
+        }
+    }
+
+    pub fn seek_to_first(&mut self) -> Result<()> {
+        match self.lower.clone() {
+            Bound::Unbounded => self.iterator.seek_to_first()?,
+            Bound::Included(key) => self.iterator.seek(&key)?,
+            Bound::Excluded(key) => {
+                self.iterator.seek(&key)?;
+                if self.iterator.valid() && self.iterator.key() == Some(key.as_slice()) {
+                    self.iterator.next()?;
+                }
+            }
+        }
+
+        self.sync_valid();
+        Ok(())
+    }
+
+    pub fn next(&mut self) -> Result<bool> {
+        if !self.valid {
+            return Ok(false);
+        }
+
+        self.iterator.next()?;
+        self.sync_valid();
+        Ok(self.valid)
+    }
+
+    pub fn valid(&self) -> bool {
+        self.valid
+    }
+
+    pub fn key(&self) -> Option<&[u8]> {
+        if self.valid {
+            self.iterator.key()
+        } else {
+            None
+        }
+    }
+
+    pub fn value(&self) -> Option<&[u8]> {
+        if self.valid {
+            self.iterator.value()
+        } else {
+            None
+        }
+    }
+
+    fn sync_valid(&mut self) {
+        self.valid = self.iterator.valid() && self.key_within_upper();
+    }
+
+    fn key_within_upper(&self) -> bool {
+        let Some(key) = self.iterator.key() else {
+            return false;
+        };
+
+        match &self.upper {
+            Bound::Unbounded => true,
+            Bound::Included(bound) => key <= bound.as_slice(),
+            Bound::Excluded(bound) => key < bound.as_slice(),
+        }
+    }
+}
+
+/// Increment `prefix`'s last byte that isn't `0xFF`, dropping every
+/// trailing `0xFF` byte first (since those can't be incremented without
+/// carrying). Returns `None` if `prefix` is empty or every byte is `0xFF` —
+/// there's no key that sorts past an all-`0xFF` prefix.
+fn increment_prefix(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut incremented = prefix.to_vec();
+    while let Some(&last) = incremented.last() {
+        if last == 0xFF {
+            incremented.pop();
+        } else {
+            *incremented.last_mut().expect("checked non-empty above") = last + 1;
+            return Some(incremented);
+        }
+    }
+    None
+}
+
+pub struct SstEntryIterator<S: BlockSource> {
+    iterator: SstTableIterator<S>,
+}
+
+impl<S: BlockSource> SstEntryIterator<S> {
+    pub fn new(sst_reader: SstReader<S>) -> Result<Self> {
+        let iterator = SstTableIterator::new(sst_reader)?;
         Ok(SstEntryIterator { iterator })
     }
 
+    /// Every resolved `(key, value)` pair in the table, in key order. If
+    /// `ReadOptions::merge_operator` is configured, consecutive records for
+    /// the same key are decoded (see [`decode_entry`]) and folded through
+    /// it; a `Delete` with no following `Merge` operands is omitted
+    /// entirely. Without a `merge_operator`, entries are returned exactly
+    /// as stored, with no `EntryType` decoding at all.
     pub fn collect_all(&mut self) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+        let raw = self.collect_raw_entries()?;
+
+        match self.iterator.read_options().merge_operator.clone() {
+            Some(operator) => resolve_merges(&raw, operator.as_ref()),
+            None => Ok(raw),
+        }
+    }
+
+    fn collect_raw_entries(&mut self) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
         let mut entries = Vec::new();
         self.iterator.seek_to_first()?;
 
@@ -247,18 +442,49 @@ impl SstEntryIterator {
         Ok(entries)
     }
 
+    /// Look up `target_key`'s resolved value, short-circuiting without
+    /// reading a data block when the table has a Bloom filter block and it
+    /// says the key is definitely absent from the candidate block. With
+    /// `ReadOptions::merge_operator` configured, every consecutive record
+    /// for `target_key` (a base `Put`/`Delete` followed by `Merge`
+    /// operands) is folded through it the same way [`Self::collect_all`]
+    /// does; without one, the raw stored value for the first matching
+    /// record is returned, as before.
     pub fn find(&mut self, target_key: &[u8]) -> Result<Option<Vec<u8>>> {
+        if !self.iterator.may_contain_key(target_key)? {
+            return Ok(None);
+        }
+
         self.iterator.seek(target_key)?;
 
-        if self.iterator.valid() {
-            if let Some(key) = self.iterator.key() {
-                if key == target_key {
-                    return Ok(self.iterator.value().map(|v| v.to_vec()));
+        if !self.iterator.valid() {
+            return Ok(None);
+        }
+
+        if self.iterator.key() != Some(target_key) {
+            return Ok(None);
+        }
+
+        let merge_operator = self.iterator.read_options().merge_operator.clone();
+        let operator = match merge_operator {
+            Some(operator) => operator,
+            None => return Ok(self.iterator.value().map(|v| v.to_vec())),
+        };
+
+        let mut run = Vec::new();
+        loop {
+            match (self.iterator.key(), self.iterator.value()) {
+                (Some(key), Some(value)) if key == target_key => {
+                    run.push((key.to_vec(), value.to_vec()));
                 }
+                _ => break,
+            }
+            if !self.iterator.next()? {
+                break;
             }
         }
 
-        Ok(None)
+        resolve_run(target_key, &run, operator.as_ref())
     }
 
     pub fn entries_count(&self) -> usize {
@@ -270,7 +496,99 @@ impl SstEntryIterator {
     }
 }
 
-impl Iterator for SstEntryIterator {
+/// Group `raw` by consecutive equal keys and resolve each group through
+/// [`resolve_run`], dropping any that resolve to absent (a `Delete` with no
+/// following merges).
+fn resolve_merges(
+    raw: &[(Vec<u8>, Vec<u8>)],
+    operator: &dyn MergeOperator,
+) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
+    let mut resolved = Vec::new();
+    let mut index = 0;
+
+    while index < raw.len() {
+        let key = &raw[index].0;
+        let mut run_end = index + 1;
+        while run_end < raw.len() && raw[run_end].0 == *key {
+            run_end += 1;
+        }
+
+        if let Some(value) = resolve_run(key, &raw[index..run_end], operator)? {
+            resolved.push((key.clone(), value));
+        }
+        index = run_end;
+    }
+
+    Ok(resolved)
+}
+
+/// Decode every entry in `run` (all sharing `key`) and fold them: a `Put`
+/// resets the base value, a `Delete` clears it, and each `Merge` collects
+/// an operand atop whichever base preceded it. If the run ends with no
+/// merge operands pending, the last base is returned directly — `None` for
+/// a `Delete`, without ever invoking `operator`. Otherwise the pending
+/// operands (first folded pairwise via `operator.partial_merge` where
+/// possible) are resolved against the base via `operator.full_merge`.
+fn resolve_run(
+    key: &[u8],
+    run: &[(Vec<u8>, Vec<u8>)],
+    operator: &dyn MergeOperator,
+) -> Result<Option<Vec<u8>>> {
+    let mut base: Option<Vec<u8>> = None;
+    let mut operands: Vec<Vec<u8>> = Vec::new();
+
+    for (_, encoded) in run {
+        let (entry_type, payload) = decode_entry(encoded)?;
+        match entry_type {
+            EntryType::Put => {
+                base = Some(payload.to_vec());
+                operands.clear();
+            }
+            EntryType::Delete => {
+                base = None;
+                operands.clear();
+            }
+            EntryType::Merge => operands.push(payload.to_vec()),
+        }
+    }
+
+    if operands.is_empty() {
+        return Ok(base);
+    }
+
+    let folded = fold_operands(key, &operands, operator);
+    Ok(operator.full_merge(key, base.as_deref(), &folded))
+}
+
+/// Fold adjacent operands pairwise via `operator.partial_merge` wherever it
+/// succeeds, leaving the rest untouched — an optional optimization; see
+/// [`MergeOperator::partial_merge`].
+fn fold_operands(key: &[u8], operands: &[Vec<u8>], operator: &dyn MergeOperator) -> Vec<Vec<u8>> {
+    let mut folded: Vec<Vec<u8>> = Vec::new();
+
+    for operand in operands {
+        if let Some(last) = folded.last_mut() {
+            if let Some(combined) = operator.partial_merge(key, last, operand) {
+                *last = combined;
+                continue;
+            }
+        }
+        folded.push(operand.clone());
+    }
+
+    folded
+}
+
+/// Split `encoded`'s leading [`EntryType`] prefix byte (see
+/// `SstFileWriter::encode_entry_value`) off from its payload.
+fn decode_entry(encoded: &[u8]) -> Result<(EntryType, &[u8])> {
+    let (prefix, payload) = encoded.split_first().ok_or_else(|| {
+        Error::InvalidBlockFormat("Entry value is missing its EntryType prefix".to_string())
+    })?;
+    Ok((EntryType::try_from(*prefix)?, payload))
+}
+
+impl<S: BlockSource> Iterator for SstEntryIterator<S> {
     type Item = Result<(Vec<u8>, Vec<u8>)>;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -292,3 +610,319 @@ impl Iterator for SstEntryIterator {
         }
     }
 }
+
+#[cfg(test)]
+#[cfg(feature = "test-support")]
+mod tests {
+    use super::*;
+    use crate::builder::SstImageBuilder;
+    use crate::filter_block::{FilterBlockBuilder, DEFAULT_BITS_PER_KEY, FILTER_BLOCK_NAME};
+    use crate::metaindex::PROPERTIES_BLOCK_NAME;
+    use crate::sst_reader::SstReader;
+    use crate::types::{ChecksumType, CompressionType};
+
+    #[test]
+    fn test_find_consults_filter_block_for_present_and_absent_keys() -> Result<()> {
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data1 = image.add_block(&[(b"key001".to_vec(), b"value001".to_vec())])?;
+        let data2 = image.add_block(&[(b"key002".to_vec(), b"value002".to_vec())])?;
+
+        let mut filter_builder = FilterBlockBuilder::new(DEFAULT_BITS_PER_KEY);
+        filter_builder.start_block(data1.offset);
+        filter_builder.add_key(b"key001");
+        filter_builder.start_block(data2.offset);
+        filter_builder.add_key(b"key002");
+        let filter_bytes = filter_builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let filter_handle = image.add_finished_block(&filter_bytes);
+
+        let index = image.add_index_block(&[
+            (b"key001".to_vec(), data1.clone()),
+            (b"key002".to_vec(), data2.clone()),
+        ])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![2])])?;
+        let metaindex = image.add_metaindex(&[
+            (FILTER_BLOCK_NAME, filter_handle),
+            (PROPERTIES_BLOCK_NAME, properties),
+        ])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let reader = SstReader::from_source(bytes)?;
+        let mut entries = SstEntryIterator::new(reader)?;
+
+        assert_eq!(entries.find(b"key001")?, Some(b"value001".to_vec()));
+        assert_eq!(entries.find(b"key002")?, Some(b"value002".to_vec()));
+        assert_eq!(entries.find(b"absent-key")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_still_works_without_a_filter_block() -> Result<()> {
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data1 = image.add_block(&[(b"key001".to_vec(), b"value001".to_vec())])?;
+
+        let index = image.add_index_block(&[(b"key001".to_vec(), data1.clone())])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let reader = SstReader::from_source(bytes)?;
+        let mut entries = SstEntryIterator::new(reader)?;
+
+        assert_eq!(entries.find(b"key001")?, Some(b"value001".to_vec()));
+        assert_eq!(entries.find(b"absent-key")?, None);
+
+        Ok(())
+    }
+
+    fn encode(entry_type: EntryType, payload: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![entry_type as u8];
+        encoded.extend_from_slice(payload);
+        encoded
+    }
+
+    #[test]
+    fn test_collect_all_folds_merge_operands_onto_put_base() -> Result<()> {
+        use crate::merge::ConcatMergeOperator;
+        use std::sync::Arc;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data = image.add_block(&[
+            (b"key".to_vec(), encode(EntryType::Put, b"a")),
+            (b"key".to_vec(), encode(EntryType::Merge, b"b")),
+            (b"key".to_vec(), encode(EntryType::Merge, b"c")),
+        ])?;
+        let index = image.add_index_block(&[(b"key".to_vec(), data.clone())])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![3])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let read_options = crate::types::ReadOptions {
+            merge_operator: Some(Arc::new(ConcatMergeOperator)),
+            ..crate::types::ReadOptions::default()
+        };
+        let reader = SstReader::from_source_with_options(bytes, read_options)?;
+        let mut entries = SstEntryIterator::new(reader)?;
+
+        assert_eq!(entries.collect_all()?, vec![(b"key".to_vec(), b"abc".to_vec())]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_folds_merge_operands_for_the_same_key() -> Result<()> {
+        use crate::merge::ConcatMergeOperator;
+        use std::sync::Arc;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data = image.add_block(&[
+            (b"key".to_vec(), encode(EntryType::Put, b"a")),
+            (b"key".to_vec(), encode(EntryType::Merge, b"b")),
+        ])?;
+        let index = image.add_index_block(&[(b"key".to_vec(), data.clone())])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![2])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let read_options = crate::types::ReadOptions {
+            merge_operator: Some(Arc::new(ConcatMergeOperator)),
+            ..crate::types::ReadOptions::default()
+        };
+        let reader = SstReader::from_source_with_options(bytes, read_options)?;
+        let mut entries = SstEntryIterator::new(reader)?;
+
+        assert_eq!(entries.find(b"key")?, Some(b"ab".to_vec()));
+        assert_eq!(entries.find(b"absent-key")?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_all_omits_a_delete_with_no_following_merges() -> Result<()> {
+        use crate::merge::ConcatMergeOperator;
+        use std::sync::Arc;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data1 = image.add_block(&[(b"deleted".to_vec(), encode(EntryType::Delete, b""))])?;
+        let data2 = image.add_block(&[(b"kept".to_vec(), encode(EntryType::Put, b"value"))])?;
+        let index = image.add_index_block(&[
+            (b"deleted".to_vec(), data1.clone()),
+            (b"kept".to_vec(), data2.clone()),
+        ])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![2])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let read_options = crate::types::ReadOptions {
+            merge_operator: Some(Arc::new(ConcatMergeOperator)),
+            ..crate::types::ReadOptions::default()
+        };
+        let reader = SstReader::from_source_with_options(bytes, read_options)?;
+        let mut entries = SstEntryIterator::new(reader)?;
+
+        assert_eq!(
+            entries.collect_all()?,
+            vec![(b"kept".to_vec(), b"value".to_vec())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_collect_all_passes_through_raw_values_without_a_merge_operator() -> Result<()> {
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data = image.add_block(&[(
+            b"key".to_vec(),
+            encode(EntryType::Put, b"a"),
+        )])?;
+        let index = image.add_index_block(&[(b"key".to_vec(), data.clone())])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let reader = SstReader::from_source(bytes)?;
+        let mut entries = SstEntryIterator::new(reader)?;
+
+        // With no merge_operator configured, the EntryType prefix is never
+        // decoded — the raw stored value (prefix byte included) comes back
+        // exactly as before this feature existed.
+        assert_eq!(
+            entries.collect_all()?,
+            vec![(b"key".to_vec(), encode(EntryType::Put, b"a"))]
+        );
+
+        Ok(())
+    }
+
+    fn two_block_table() -> Result<SstReader<Vec<u8>>> {
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data1 = image.add_block(&[
+            (b"key001".to_vec(), b"value001".to_vec()),
+            (b"key003".to_vec(), b"value003".to_vec()),
+        ])?;
+        let data2 = image.add_block(&[
+            (b"key005".to_vec(), b"value005".to_vec()),
+            (b"key007".to_vec(), b"value007".to_vec()),
+        ])?;
+        let index = image.add_index_block(&[
+            (b"key003".to_vec(), data1.clone()),
+            (b"key007".to_vec(), data2.clone()),
+        ])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![4])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, index)?;
+        SstReader::from_source(bytes)
+    }
+
+    #[test]
+    fn test_prev_walks_backward_across_block_boundaries() -> Result<()> {
+        let reader = two_block_table()?;
+        let mut iterator = SstTableIterator::new(reader)?;
+
+        iterator.seek_to_last()?;
+        assert_eq!(iterator.key(), Some(b"key007".as_slice()));
+
+        assert!(iterator.prev()?);
+        assert_eq!(iterator.key(), Some(b"key005".as_slice()));
+
+        // Crosses from the second block back into the first.
+        assert!(iterator.prev()?);
+        assert_eq!(iterator.key(), Some(b"key003".as_slice()));
+
+        assert!(iterator.prev()?);
+        assert_eq!(iterator.key(), Some(b"key001".as_slice()));
+
+        assert!(!iterator.prev()?);
+        assert!(!iterator.valid());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek_for_prev_lands_on_the_largest_key_less_or_equal() -> Result<()> {
+        let reader = two_block_table()?;
+        let mut iterator = SstTableIterator::new(reader)?;
+
+        // Exact match.
+        iterator.seek_for_prev(b"key005")?;
+        assert_eq!(iterator.key(), Some(b"key005".as_slice()));
+
+        // Between two keys in the same block lands on the smaller one.
+        iterator.seek_for_prev(b"key002")?;
+        assert_eq!(iterator.key(), Some(b"key001".as_slice()));
+
+        // Falls back into the first block when the index picks a later
+        // block whose own keys are all greater than the target.
+        iterator.seek_for_prev(b"key004")?;
+        assert_eq!(iterator.key(), Some(b"key003".as_slice()));
+
+        // Past the last key lands on the last entry.
+        iterator.seek_for_prev(b"key999")?;
+        assert_eq!(iterator.key(), Some(b"key007".as_slice()));
+
+        // Before the first key finds nothing.
+        iterator.seek_for_prev(b"key000")?;
+        assert!(!iterator.valid());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_iterator_prefix_scan_stops_at_the_next_prefix() -> Result<()> {
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data1 = image.add_block(&[
+            (b"a1".to_vec(), b"1".to_vec()),
+            (b"a2".to_vec(), b"2".to_vec()),
+        ])?;
+        let data2 = image.add_block(&[(b"b1".to_vec(), b"3".to_vec())])?;
+        let index = image.add_index_block(&[
+            (b"a2".to_vec(), data1.clone()),
+            (b"b1".to_vec(), data2.clone()),
+        ])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![3])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let reader = SstReader::from_source(bytes)?;
+        let table_iterator = SstTableIterator::new(reader)?;
+        let mut range = SstRangeIterator::prefix(table_iterator, b"a");
+
+        range.seek_to_first()?;
+        let mut collected = Vec::new();
+        while range.valid() {
+            if let (Some(key), Some(value)) = (range.key(), range.value()) {
+                collected.push((key.to_vec(), value.to_vec()));
+            }
+            range.next()?;
+        }
+
+        assert_eq!(
+            collected,
+            vec![(b"a1".to_vec(), b"1".to_vec()), (b"a2".to_vec(), b"2".to_vec())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_iterator_bounds_seek_directly_to_the_first_in_range_block() -> Result<()> {
+        let reader = two_block_table()?;
+        let table_iterator = SstTableIterator::new(reader)?;
+        let mut range = SstRangeIterator::new(
+            table_iterator,
+            Bound::Included(b"key005".to_vec()),
+            Bound::Unbounded,
+        );
+
+        range.seek_to_first()?;
+        assert_eq!(range.key(), Some(b"key005".as_slice()));
+
+        assert!(range.next()?);
+        assert_eq!(range.key(), Some(b"key007".as_slice()));
+
+        assert!(!range.next()?);
+        assert!(!range.valid());
+
+        Ok(())
+    }
+}
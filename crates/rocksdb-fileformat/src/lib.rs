@@ -3,23 +3,62 @@
 
 pub mod block_builder;
 pub mod block_handle;
+pub mod block_source;
+pub mod block_trailer;
+#[cfg(feature = "test-support")]
+pub mod builder;
+pub mod comparator;
 pub mod compression;
+pub mod compressor;
+pub mod cursor;
 pub mod data_block;
+pub mod encryption;
 pub mod error;
+pub mod filter_block;
 pub mod footer;
 pub mod index_block;
+pub mod integrity;
 pub mod iterator;
+pub mod merge;
+pub mod metaindex;
+#[cfg(feature = "mmap")]
+pub mod mmap_source;
 pub mod sst_file_writer;
 pub mod sst_reader;
+pub mod split_source;
 pub mod types;
 
 pub use block_handle::BlockHandle;
-pub use compression::{compress, decompress};
+pub use block_source::BlockSource;
+pub use block_trailer::verify_block;
+#[cfg(feature = "test-support")]
+pub use builder::{corrupt_magic, corrupt_v6_reserved_field, truncate_footer, SstImageBuilder};
+pub use comparator::{BytewiseComparator, Comparator, InternalKeyComparator};
+pub use compression::{
+    compress, compress_by_id, compress_with_dict, compress_with_options, decompress,
+    decompress_by_id, decompress_with_dict, CompressionOptions,
+};
+#[cfg(feature = "compress-zstd")]
+pub use compression::{compress_zstd_with_dict, decompress_zstd_with_dict, train_zstd_dictionary};
+pub use compressor::{Compressor, CompressorRegistry};
+pub use cursor::BinCursor;
 pub use data_block::{DataBlock, DataBlockReader, KeyValue};
+pub use encryption::{iv_for_offset, Encryptor};
 pub use error::{Error, Result};
+pub use filter_block::{
+    FilterBlockBuilder, FilterBlockReader, DEFAULT_BITS_PER_KEY, FILTER_BLOCK_NAME,
+};
 pub use footer::Footer;
-pub use index_block::{IndexBlock, IndexEntry};
-pub use iterator::{SstEntryIterator, SstIterator, SstTableIterator};
+pub use index_block::{IndexBlock, IndexEntry, IndexValueFormat, ScanStats};
+pub use integrity::{FileIntegrityDigest, FILE_INTEGRITY_BLOCK_NAME};
+pub use iterator::{SstEntryIterator, SstIterator, SstRangeIterator, SstTableIterator};
+pub use merge::{ConcatMergeOperator, MergeOperator};
+pub use metaindex::{
+    decode_metaindex, TableProperties, COMPRESSION_DICT_BLOCK_NAME, PROPERTIES_BLOCK_NAME,
+};
+#[cfg(feature = "mmap")]
+pub use mmap_source::MmapFileSource;
 pub use sst_file_writer::{EntryType, SstFileWriter};
-pub use sst_reader::SstReader;
-pub use types::{ChecksumType, CompressionType, FormatVersion, ReadOptions, WriteOptions};
+pub use sst_reader::{FileSource, SstReader, ValidationReport};
+pub use split_source::SplitFileSource;
+pub use types::{ChecksumHasher, ChecksumType, CompressionType, FormatVersion, ReadOptions, WriteOptions};
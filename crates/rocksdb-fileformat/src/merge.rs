@@ -0,0 +1,92 @@
+// Copyright 2024 YaleDB Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolving `EntryType::Merge` records (see [`crate::sst_file_writer::EntryType`])
+//! against the base record and any other merge operands that came before
+//! them for the same key, the way [`crate::iterator::SstEntryIterator`]
+//! does when `ReadOptions::merge_operator` is configured.
+
+/// Collapses a base record (a `Put`'s value, or `None` for a `Delete` or a
+/// key with no base record at all) and the `Merge` operands recorded after
+/// it, in order, into the key's final resolved value.
+pub trait MergeOperator: Send + Sync {
+    /// Resolve `existing` against every one of `operands`, applied in
+    /// order. Returning `None` resolves the key to "absent" — e.g. a
+    /// `Delete` that no later operand repopulated.
+    fn full_merge(
+        &self,
+        key: &[u8],
+        existing: Option<&[u8]>,
+        operands: &[Vec<u8>],
+    ) -> Option<Vec<u8>>;
+
+    /// Combine two adjacent operands into one equivalent operand, without
+    /// needing `existing` — an optimization available only when the merge
+    /// is associative. [`crate::iterator::SstEntryIterator`] uses this to
+    /// fold a run of operands down before the final [`Self::full_merge`]
+    /// call. Returning `None` means the pair can't be combined ahead of
+    /// time; both are kept and passed to `full_merge` separately. Default:
+    /// unsupported.
+    fn partial_merge(&self, _key: &[u8], _left: &[u8], _right: &[u8]) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Reference [`MergeOperator`]: appends each operand directly onto the
+/// previous value (or its neighboring operand, for [`Self::partial_merge`]),
+/// with no delimiter between them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConcatMergeOperator;
+
+impl MergeOperator for ConcatMergeOperator {
+    fn full_merge(
+        &self,
+        _key: &[u8],
+        existing: Option<&[u8]>,
+        operands: &[Vec<u8>],
+    ) -> Option<Vec<u8>> {
+        let mut result = existing.map(|v| v.to_vec()).unwrap_or_default();
+        for operand in operands {
+            result.extend_from_slice(operand);
+        }
+        Some(result)
+    }
+
+    fn partial_merge(&self, _key: &[u8], left: &[u8], right: &[u8]) -> Option<Vec<u8>> {
+        let mut combined = left.to_vec();
+        combined.extend_from_slice(right);
+        Some(combined)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat_full_merge_appends_operands_onto_existing() {
+        let op = ConcatMergeOperator;
+        let operands = vec![b"b".to_vec(), b"c".to_vec()];
+        assert_eq!(
+            op.full_merge(b"key", Some(b"a"), &operands),
+            Some(b"abc".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_concat_full_merge_with_no_existing_starts_from_empty() {
+        let op = ConcatMergeOperator;
+        let operands = vec![b"a".to_vec(), b"b".to_vec()];
+        assert_eq!(op.full_merge(b"key", None, &operands), Some(b"ab".to_vec()));
+    }
+
+    #[test]
+    fn test_concat_partial_merge_is_associative_with_full_merge() {
+        let op = ConcatMergeOperator;
+        let folded = op.partial_merge(b"key", b"a", b"b").unwrap();
+        assert_eq!(
+            op.full_merge(b"key", None, &[folded, b"c".to_vec()]),
+            op.full_merge(b"key", None, &[b"a".to_vec(), b"b".to_vec(), b"c".to_vec()])
+        );
+    }
+}
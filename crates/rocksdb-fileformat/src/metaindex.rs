@@ -0,0 +1,218 @@
+// Copyright 2024 YaleDB Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Parsing for the metaindex block and the `rocksdb.properties` table
+//! properties block it points to.
+
+use crate::block_handle::BlockHandle;
+use crate::data_block::KeyValue;
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+
+/// Name of the metaindex entry that points at the table properties block.
+pub const PROPERTIES_BLOCK_NAME: &str = "rocksdb.properties";
+
+/// Name of the metaindex entry that points at the shared compression
+/// dictionary block, when the table was written with one.
+pub const COMPRESSION_DICT_BLOCK_NAME: &str = "rocksdb.compression_dict";
+
+/// Look up `name` among the metaindex block's already-decoded entries and
+/// decode its value as a [`BlockHandle`]. Metaindex values are encoded the
+/// same way as index block values: a varint64 offset followed by a varint64
+/// size.
+pub fn find_metaindex_entry(entries: &[KeyValue], name: &str) -> Result<Option<BlockHandle>> {
+    for entry in entries {
+        if entry.key == name.as_bytes() {
+            let (handle, _) = BlockHandle::decode_from_bytes(&entry.value)?;
+            return Ok(Some(handle));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Decode every entry of an already-decoded metaindex block into a
+/// name -> handle map, covering `rocksdb.properties` alongside any filter or
+/// per-column-family blocks a writer recorded. Entries whose key is not
+/// valid UTF-8 are skipped rather than failing the whole decode, since a
+/// meta-block name is conventionally ASCII but nothing in the format
+/// guarantees it.
+pub fn decode_metaindex(entries: &[KeyValue]) -> Result<BTreeMap<String, BlockHandle>> {
+    let mut map = BTreeMap::new();
+
+    for entry in entries {
+        let Ok(name) = std::str::from_utf8(&entry.key) else {
+            continue;
+        };
+        let (handle, _) = BlockHandle::decode_from_bytes(&entry.value)?;
+        map.insert(name.to_string(), handle);
+    }
+
+    Ok(map)
+}
+
+/// Decoded `rocksdb.properties` block: per-table statistics and metadata
+/// RocksDB records alongside the data (entry counts, raw/compressed sizes,
+/// comparator name, creation time, …). Numeric properties are stored as
+/// varint64 values; string properties are stored as raw UTF-8 bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableProperties {
+    pub data_size: u64,
+    pub index_size: u64,
+    pub filter_size: u64,
+    pub raw_key_size: u64,
+    pub raw_value_size: u64,
+    pub num_data_blocks: u64,
+    pub num_entries: u64,
+    pub format_version: u64,
+    pub fixed_key_length: u64,
+    pub column_family_id: u64,
+    pub creation_time: u64,
+    pub comparator_name: String,
+    pub merge_operator_name: String,
+    pub compression_name: String,
+}
+
+impl TableProperties {
+    const DATA_SIZE: &'static str = "rocksdb.data.size";
+    const INDEX_SIZE: &'static str = "rocksdb.index.size";
+    const FILTER_SIZE: &'static str = "rocksdb.filter.size";
+    const RAW_KEY_SIZE: &'static str = "rocksdb.raw.key.size";
+    const RAW_VALUE_SIZE: &'static str = "rocksdb.raw.value.size";
+    const NUM_DATA_BLOCKS: &'static str = "rocksdb.num.data.blocks";
+    const NUM_ENTRIES: &'static str = "rocksdb.num.entries";
+    const FORMAT_VERSION: &'static str = "rocksdb.format.version";
+    const FIXED_KEY_LENGTH: &'static str = "rocksdb.fixed.key.length";
+    const COLUMN_FAMILY_ID: &'static str = "rocksdb.column.family.id";
+    const CREATION_TIME: &'static str = "rocksdb.creation.time";
+    const COMPARATOR: &'static str = "rocksdb.comparator";
+    const MERGE_OPERATOR: &'static str = "rocksdb.merge.operator";
+    const COMPRESSION: &'static str = "rocksdb.compression";
+
+    /// Parse the key/value entries already decoded from a properties block.
+    /// Unrecognized property names are ignored, matching RocksDB's own
+    /// tolerance for properties written by a newer version of the format.
+    pub fn from_entries(entries: &[KeyValue]) -> Result<Self> {
+        let mut props = TableProperties::default();
+
+        for entry in entries {
+            let name = std::str::from_utf8(&entry.key)
+                .map_err(|_| Error::InvalidBlockFormat("Non-UTF8 property name".to_string()))?;
+
+            match name {
+                Self::DATA_SIZE => props.data_size = decode_varint64(&entry.value)?,
+                Self::INDEX_SIZE => props.index_size = decode_varint64(&entry.value)?,
+                Self::FILTER_SIZE => props.filter_size = decode_varint64(&entry.value)?,
+                Self::RAW_KEY_SIZE => props.raw_key_size = decode_varint64(&entry.value)?,
+                Self::RAW_VALUE_SIZE => props.raw_value_size = decode_varint64(&entry.value)?,
+                Self::NUM_DATA_BLOCKS => props.num_data_blocks = decode_varint64(&entry.value)?,
+                Self::NUM_ENTRIES => props.num_entries = decode_varint64(&entry.value)?,
+                Self::FORMAT_VERSION => props.format_version = decode_varint64(&entry.value)?,
+                Self::FIXED_KEY_LENGTH => props.fixed_key_length = decode_varint64(&entry.value)?,
+                Self::COLUMN_FAMILY_ID => props.column_family_id = decode_varint64(&entry.value)?,
+                Self::CREATION_TIME => props.creation_time = decode_varint64(&entry.value)?,
+                Self::COMPARATOR => {
+                    props.comparator_name = String::from_utf8_lossy(&entry.value).into_owned()
+                }
+                Self::MERGE_OPERATOR => {
+                    props.merge_operator_name = String::from_utf8_lossy(&entry.value).into_owned()
+                }
+                Self::COMPRESSION => {
+                    props.compression_name = String::from_utf8_lossy(&entry.value).into_owned()
+                }
+                _ => {}
+            }
+        }
+
+        Ok(props)
+    }
+}
+
+fn decode_varint64(data: &[u8]) -> Result<u64> {
+    let mut result = 0u64;
+    let mut shift = 0;
+
+    for &byte in data {
+        if shift >= 64 {
+            return Err(Error::InvalidVarint);
+        }
+
+        result |= ((byte & 0x7f) as u64) << shift;
+        if (byte & 0x80) == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+    }
+
+    Err(Error::InvalidVarint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kv(key: &[u8], value: Vec<u8>) -> KeyValue {
+        KeyValue {
+            key: key.to_vec(),
+            value,
+        }
+    }
+
+    fn encode_varint64(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+        out
+    }
+
+    #[test]
+    fn test_find_metaindex_entry() -> Result<()> {
+        let handle = BlockHandle::new(1234, 56);
+        let entries = vec![kv(b"rocksdb.properties", handle.encode_to_bytes()?)];
+
+        let found = find_metaindex_entry(&entries, PROPERTIES_BLOCK_NAME)?;
+        assert_eq!(found, Some(handle));
+
+        let missing = find_metaindex_entry(&entries, "rocksdb.filter.bloom")?;
+        assert_eq!(missing, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_metaindex() -> Result<()> {
+        let properties_handle = BlockHandle::new(1234, 56);
+        let filter_handle = BlockHandle::new(7000, 200);
+        let entries = vec![
+            kv(b"rocksdb.properties", properties_handle.encode_to_bytes()?),
+            kv(b"filter.rocksdb.BuiltinBloomFilter", filter_handle.encode_to_bytes()?),
+        ];
+
+        let map = decode_metaindex(&entries)?;
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(PROPERTIES_BLOCK_NAME), Some(&properties_handle));
+        assert_eq!(
+            map.get("filter.rocksdb.BuiltinBloomFilter"),
+            Some(&filter_handle)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_properties_from_entries() -> Result<()> {
+        let entries = vec![
+            kv(b"rocksdb.data.size", encode_varint64(4096)),
+            kv(b"rocksdb.num.entries", encode_varint64(42)),
+            kv(b"rocksdb.comparator", b"leveldb.BytewiseComparator".to_vec()),
+            kv(b"rocksdb.some.future.property", b"ignored".to_vec()),
+        ];
+
+        let props = TableProperties::from_entries(&entries)?;
+        assert_eq!(props.data_size, 4096);
+        assert_eq!(props.num_entries, 42);
+        assert_eq!(props.comparator_name, "leveldb.BytewiseComparator");
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2024 YaleDB Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An mmap-backed [`BlockSource`] alternative to [`crate::sst_reader::FileSource`].
+//!
+//! [`MmapFileSource`] maps the whole SST file into memory once at
+//! construction, so every later [`BlockSource::read_at`] call is a plain
+//! memory copy out of the mapping instead of a `pread` syscall — and a
+//! block re-read from a page already faulted in (a hot index block, say)
+//! doesn't re-enter the kernel at all. This eliminates the per-block read
+//! syscall [`crate::sst_reader::SstReader::read_data_block`] would
+//! otherwise make against a plain `File`.
+//!
+//! It does not (yet) make [`crate::data_block::DataBlockReader`] borrow its
+//! key/value slices directly out of the mapping — every entry there is
+//! still an owned `Vec<u8>`, copied out of whatever buffer `read_at` filled
+//! (see [`crate::data_block::KeyValue`]), so this backend removes the
+//! syscall and page-cache round trip but not that allocation. Doing better
+//! would mean threading a lifetime through `DataBlock`/`DataBlockReader`
+//! and every type built on them (`SstReader`, `SstTableIterator`,
+//! `SstEntryIterator`), which is a larger, separate change; this backend is
+//! still a strict improvement for the syscall-heavy case (full scans,
+//! random point lookups against a warm page cache) and the existing
+//! `File`-backed path remains exactly as it was for callers who don't want
+//! the mmap tradeoffs below.
+//!
+//! # Safety considerations
+//!
+//! Memory-mapped I/O can't detect another process truncating or rewriting
+//! the underlying file out from under the mapping — doing so is undefined
+//! behavior at the OS level, not just a logical error this type can guard
+//! against. Prefer [`crate::sst_reader::FileSource`] when the file might be
+//! modified concurrently with reads.
+
+use crate::block_source::BlockSource;
+use crate::error::Result;
+use memmap2::Mmap;
+use std::fs::File;
+use std::path::Path;
+
+/// A [`BlockSource`] backed by a read-only memory map of the whole file.
+/// See the module docs for what this does and doesn't buy over
+/// [`crate::sst_reader::FileSource`].
+pub struct MmapFileSource {
+    mmap: Mmap,
+}
+
+impl MmapFileSource {
+    /// Map `path` read-only for the lifetime of this source.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: see the module-level "Safety considerations" note — the
+        // caller is responsible for not mutating the file out from under
+        // this mapping for as long as this `MmapFileSource` is alive.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(MmapFileSource { mmap })
+    }
+}
+
+impl BlockSource for MmapFileSource {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        self.mmap.as_ref().read_at(offset, buf)
+    }
+
+    fn len(&self) -> u64 {
+        self.mmap.len() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn temp_file_with(contents: &[u8]) -> Result<NamedTempFile> {
+        let mut file = NamedTempFile::new()
+            .map_err(|e| Error::InvalidArgument(format!("Temp file failed: {}", e)))?;
+        file.write_all(contents)?;
+        file.flush()?;
+        Ok(file)
+    }
+
+    #[test]
+    fn test_read_at_matches_file_contents() -> Result<()> {
+        let file = temp_file_with(b"hello world")?;
+
+        let source = MmapFileSource::open(file.path())?;
+        assert_eq!(source.len(), 11);
+
+        let mut buf = [0u8; 5];
+        source.read_at(6, &mut buf)?;
+        assert_eq!(&buf, b"world");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_at_out_of_bounds_fails() -> Result<()> {
+        let file = temp_file_with(b"hi")?;
+
+        let source = MmapFileSource::open(file.path())?;
+        let mut buf = [0u8; 5];
+        assert!(source.read_at(0, &mut buf).is_err());
+        Ok(())
+    }
+}
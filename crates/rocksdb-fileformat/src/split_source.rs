@@ -0,0 +1,195 @@
+// Copyright 2024 YaleDB Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::block_source::BlockSource;
+use crate::error::{Error, Result};
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+
+/// One physical part of a split SST, positioned at `start` within the
+/// combined logical file.
+struct Part {
+    file: File,
+    start: u64,
+    len: u64,
+}
+
+/// A [`BlockSource`] that logically concatenates an SST split across
+/// sequentially-numbered part files (`<path>.000`, `<path>.001`, …), the way
+/// large SST exports are frequently chunked before the footer at the tail
+/// becomes reachable. `read_at` transparently spans part boundaries so
+/// [`crate::footer::Footer::read_from_source`] and `SstReader::read_block`
+/// see one contiguous byte stream regardless of how the parts are split.
+pub struct SplitFileSource {
+    parts: Vec<Part>,
+    total_len: u64,
+}
+
+impl SplitFileSource {
+    /// Probe for a split set based at `path`: `<path>.000`, `<path>.001`, …
+    /// Returns `Ok(None)` if `<path>.000` doesn't exist, so callers can fall
+    /// back to treating `path` as a single ordinary file.
+    pub fn probe(path: &Path) -> Result<Option<Self>> {
+        let mut parts = Vec::new();
+        let mut offset = 0u64;
+        let mut index = 0u32;
+
+        loop {
+            let part_path = Self::part_path(path, index);
+            if !part_path.exists() {
+                break;
+            }
+
+            let file = File::open(&part_path)?;
+            let len = file.metadata()?.len();
+            parts.push(Part {
+                file,
+                start: offset,
+                len,
+            });
+            offset += len;
+            index += 1;
+        }
+
+        if parts.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(SplitFileSource {
+            parts,
+            total_len: offset,
+        }))
+    }
+
+    fn part_path(base: &Path, index: u32) -> PathBuf {
+        let mut name = base.as_os_str().to_owned();
+        name.push(format!(".{:03}", index));
+        PathBuf::from(name)
+    }
+
+    fn part_for_offset(&self, offset: u64) -> Result<usize> {
+        self.parts
+            .binary_search_by(|part| {
+                if offset < part.start {
+                    std::cmp::Ordering::Greater
+                } else if offset >= part.start + part.len {
+                    std::cmp::Ordering::Less
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .map_err(|_| {
+                Error::InvalidArgument(format!(
+                    "read_at offset {} outside split file bounds (total {} bytes)",
+                    offset, self.total_len
+                ))
+            })
+    }
+}
+
+impl BlockSource for SplitFileSource {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        let end = offset
+            .checked_add(buf.len() as u64)
+            .ok_or_else(|| Error::InvalidArgument("read_at offset overflow".to_string()))?;
+
+        if end > self.total_len {
+            return Err(Error::InvalidArgument(format!(
+                "read_at out of bounds: requested [{}, {}) but split file is {} bytes",
+                offset, end, self.total_len
+            )));
+        }
+
+        let mut part_index = self.part_for_offset(offset)?;
+        let mut remaining = buf;
+        let mut pos = offset;
+
+        while !remaining.is_empty() {
+            let part = &self.parts[part_index];
+            let part_offset = pos - part.start;
+            let available = part.len - part_offset;
+            let chunk_len = remaining.len().min(available as usize);
+
+            FileExt::read_exact_at(&part.file, &mut remaining[..chunk_len], part_offset)?;
+
+            pos += chunk_len as u64;
+            remaining = &mut remaining[chunk_len..];
+            part_index += 1;
+        }
+
+        Ok(())
+    }
+
+    fn len(&self) -> u64 {
+        self.total_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::tempdir;
+
+    fn write_part(dir: &Path, name: &str, contents: &[u8]) {
+        let mut file = File::create(dir.join(name)).expect("create part");
+        file.write_all(contents).expect("write part");
+    }
+
+    #[test]
+    fn test_probe_no_split_returns_none() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let base = dir.path().join("table.sst");
+
+        assert!(SplitFileSource::probe(&base)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_probe_and_read_across_parts() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let base = dir.path().join("table.sst");
+
+        write_part(dir.path(), "table.sst.000", b"hello ");
+        write_part(dir.path(), "table.sst.001", b"world");
+
+        let source = SplitFileSource::probe(&base)?.expect("split set should be found");
+        assert_eq!(source.len(), 11);
+
+        let mut buf = vec![0u8; 11];
+        source.read_at(0, &mut buf)?;
+        assert_eq!(&buf, b"hello world");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_spans_part_boundary() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let base = dir.path().join("table.sst");
+
+        write_part(dir.path(), "table.sst.000", b"hello ");
+        write_part(dir.path(), "table.sst.001", b"world");
+
+        let source = SplitFileSource::probe(&base)?.expect("split set should be found");
+
+        let mut buf = [0u8; 5];
+        source.read_at(3, &mut buf)?;
+        assert_eq!(&buf, b"lo wo");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_out_of_bounds() -> Result<()> {
+        let dir = tempdir().unwrap();
+        let base = dir.path().join("table.sst");
+
+        write_part(dir.path(), "table.sst.000", b"hello");
+
+        let source = SplitFileSource::probe(&base)?.expect("split set should be found");
+
+        let mut buf = [0u8; 1];
+        assert!(source.read_at(5, &mut buf).is_err());
+        Ok(())
+    }
+}
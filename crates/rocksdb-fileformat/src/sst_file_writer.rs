@@ -1,24 +1,39 @@
-use crate::block_builder::{DataBlockBuilder, IndexBlockBuilder};
+use crate::block_builder::{
+    checksum_for_trailer, DataBlockBuilder, DataBlockBuilderOptions, IndexBlockBuilder,
+};
 use crate::block_handle::BlockHandle;
 use crate::error::{Error, Result};
 use crate::footer::Footer;
-use crate::types::{CompressionType, Options};
+use crate::types::{ChecksumType, CompressionType, WriteOptions};
 use byteorder::{LittleEndian, WriteBytesExt};
 use std::fs::File;
 use std::io::{BufWriter, Write};
 use std::path::Path;
 
-/// Entry type for SST files  
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Entry type for SST files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EntryType {
-    Put,
-    Delete,
-    Merge,
+    Put = 0,
+    Delete = 1,
+    Merge = 2,
+}
+
+impl TryFrom<u8> for EntryType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            0 => Ok(EntryType::Put),
+            1 => Ok(EntryType::Delete),
+            2 => Ok(EntryType::Merge),
+            _ => Err(Error::UnsupportedEntryType(value)),
+        }
+    }
 }
 
 /// SST file writer that matches RocksDB's SstFileWriter API
 pub struct SstFileWriter {
-    options: Options,
+    options: WriteOptions,
     writer: Option<BufWriter<File>>,
     data_block_builder: DataBlockBuilder,
     index_block_builder: IndexBlockBuilder,
@@ -31,11 +46,13 @@ pub struct SstFileWriter {
 
 impl SstFileWriter {
     /// Create a new SstFileWriter with the given options
-    pub fn create(opts: &Options) -> Self {
+    pub fn create(opts: &WriteOptions) -> Self {
         SstFileWriter {
             options: opts.clone(),
             writer: None,
-            data_block_builder: DataBlockBuilder::new(opts.block_restart_interval),
+            data_block_builder: DataBlockBuilder::new(
+                DataBlockBuilderOptions::default().with_restart_interval(opts.block_restart_interval),
+            ),
             index_block_builder: IndexBlockBuilder::new(opts.block_restart_interval),
             offset: 0,
             num_entries: 0,
@@ -100,7 +117,9 @@ impl SstFileWriter {
         }
 
         // Prepare all data to write
-        let index_block_data = self.index_block_builder.finish(CompressionType::None)?;
+        let index_block_data =
+            self.index_block_builder
+                .finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
         let index_handle = BlockHandle {
             offset: self.offset,
             size: index_block_data.len() as u64,
@@ -113,13 +132,6 @@ impl SstFileWriter {
             size: metaindex_data.len() as u64,
         };
 
-        let footer = Footer {
-            metaindex_handle,
-            index_handle,
-            format_version: self.options.format_version as u32,
-        };
-        let footer_data = footer.encode_to_bytes()?;
-
         // Now write everything
         let writer = self.writer.as_mut().unwrap();
         writer.write_all(&index_block_data)?;
@@ -128,6 +140,15 @@ impl SstFileWriter {
         writer.write_all(&metaindex_data)?;
         self.offset += metaindex_data.len() as u64;
 
+        let footer = Footer {
+            checksum_type: ChecksumType::CRC32c,
+            metaindex_handle,
+            index_handle,
+            format_version: self.options.format_version as u32,
+            base_context_checksum: None,
+        };
+        let footer_data = footer.encode_to_bytes(self.offset)?;
+
         writer.write_all(&footer_data)?;
 
         writer.flush()?;
@@ -184,8 +205,16 @@ impl SstFileWriter {
 
         let writer = self.writer.as_mut().unwrap();
 
-        // Finish the current data block
-        let block_data = self.data_block_builder.finish(self.options.compression)?;
+        // Finish the current data block, with a real masked CRC32C trailer
+        // checksum rather than a dummy placeholder (see
+        // `create_empty_metaindex_block`'s doc comment for why CRC32C is
+        // hardcoded here rather than threaded through `self.options`).
+        let block_data = self.data_block_builder.finish(
+            self.options.compression,
+            ChecksumType::CRC32c,
+            None,
+            None,
+        )?;
 
         // Create block handle
         let block_handle = BlockHandle {
@@ -221,6 +250,14 @@ impl SstFileWriter {
         encoded
     }
 
+    /// Build an empty metaindex block with a real masked CRC32C trailer
+    /// checksum, computed the same way every other block in this crate's
+    /// trailer is (`crate::block_builder::checksum_for_trailer`), rather
+    /// than the dummy all-zero checksum this used to write. CRC32C is
+    /// hardcoded rather than read from `self.options`, since this writer's
+    /// `WriteOptions` has no checksum-type field of its own; `SstReader` already
+    /// verifies (or, via `ReadOptions::verify_meta_blocks`, tolerates) either
+    /// way.
     fn create_empty_metaindex_block(&self) -> Result<Vec<u8>> {
         // Create an empty metaindex block
         let mut block_data = Vec::new();
@@ -231,7 +268,8 @@ impl SstFileWriter {
 
         // Add block trailer: compression type (1 byte) + checksum (4 bytes)
         block_data.push(CompressionType::None as u8);
-        block_data.write_u32::<LittleEndian>(0)?; // dummy checksum
+        let checksum = checksum_for_trailer(ChecksumType::CRC32c, &block_data, None, None);
+        block_data.write_u32::<LittleEndian>(checksum)?;
 
         Ok(block_data)
     }
@@ -256,7 +294,7 @@ mod tests {
 
     #[test]
     fn test_create_writer() -> Result<()> {
-        let opts = Options::default();
+        let opts = WriteOptions::default();
         let writer = SstFileWriter::create(&opts);
         assert_eq!(writer.file_size(), 0);
         Ok(())
@@ -268,11 +306,12 @@ mod tests {
             tempdir().map_err(|e| Error::InvalidArgument(format!("Temp dir failed: {}", e)))?;
         let path = dir.path().join("test.sst");
 
-        let opts = Options {
+        let opts = WriteOptions {
             compression: CompressionType::None,
             block_size: 4096,
             block_restart_interval: 16,
             format_version: FormatVersion::V5,
+            ..WriteOptions::default()
         };
 
         // Write data
@@ -287,7 +326,7 @@ mod tests {
 
         // Read data back
         let mut reader = SstReader::open(&path)?;
-        let footer = reader.read_footer()?;
+        let footer = reader.get_footer();
         assert!(footer.index_handle.size > 0);
         Ok(())
     }
@@ -298,7 +337,7 @@ mod tests {
             tempdir().map_err(|e| Error::InvalidArgument(format!("Temp dir failed: {}", e)))?;
         let path = dir.path().join("test.sst");
 
-        let opts = Options::default();
+        let opts = WriteOptions::default();
         let mut writer = SstFileWriter::create(&opts);
         writer.open(&path)?;
 
@@ -316,7 +355,7 @@ mod tests {
             tempdir().map_err(|e| Error::InvalidArgument(format!("Temp dir failed: {}", e)))?;
         let path = dir.path().join("test.sst");
 
-        let opts = Options::default();
+        let opts = WriteOptions::default();
         let mut writer = SstFileWriter::create(&opts);
         writer.open(&path)?;
 
@@ -335,11 +374,12 @@ mod tests {
             tempdir().map_err(|e| Error::InvalidArgument(format!("Temp dir failed: {}", e)))?;
         let path = dir.path().join("test.sst");
 
-        let opts = Options {
+        let opts = WriteOptions {
             compression: CompressionType::Snappy,
             block_size: 1024, // Small block size to ensure compression
             block_restart_interval: 16,
             format_version: FormatVersion::V5,
+            ..WriteOptions::default()
         };
 
         let mut writer = SstFileWriter::create(&opts);
@@ -363,7 +403,7 @@ mod tests {
             tempdir().map_err(|e| Error::InvalidArgument(format!("Temp dir failed: {}", e)))?;
         let path = dir.path().join("empty.sst");
 
-        let opts = Options::default();
+        let opts = WriteOptions::default();
         let mut writer = SstFileWriter::create(&opts);
         writer.open(&path)?;
         writer.finish()?;
@@ -375,7 +415,7 @@ mod tests {
 
     #[test]
     fn test_file_not_open() -> Result<()> {
-        let opts = Options::default();
+        let opts = WriteOptions::default();
         let mut writer = SstFileWriter::create(&opts);
 
         // Should fail when no file is open
@@ -390,7 +430,7 @@ mod tests {
             tempdir().map_err(|e| Error::InvalidArgument(format!("Temp dir failed: {}", e)))?;
         let path = dir.path().join("test.sst");
 
-        let opts = Options::default();
+        let opts = WriteOptions::default();
         let mut writer = SstFileWriter::create(&opts);
         writer.open(&path)?;
         writer.finish()?;
@@ -1,32 +1,122 @@
 use crate::block_handle::BlockHandle;
-use crate::data_block::{DataBlock, DataBlockReader};
+use crate::block_source::BlockSource;
+use crate::block_trailer::{self, BLOCK_TRAILER_SIZE};
+use crate::compression::decompress;
+use crate::data_block::{DataBlock, DataBlockReader, KeyValue};
 use crate::error::{Error, Result};
+use crate::filter_block::{FilterBlockReader, FILTER_BLOCK_NAME};
 use crate::footer::Footer;
-use crate::types::CompressionType;
+use crate::index_block::IndexBlock;
+use crate::integrity::{FileIntegrityDigest, FILE_INTEGRITY_BLOCK_NAME};
+use crate::metaindex::{self, TableProperties, COMPRESSION_DICT_BLOCK_NAME, PROPERTIES_BLOCK_NAME};
+use crate::split_source::SplitFileSource;
+use crate::types::{CompressionType, ReadOptions};
+use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 
-pub struct SstReader {
-    reader: BufReader<File>,
+/// The [`BlockSource`] behind [`SstReader::open`]: either a single ordinary
+/// file, or a [`SplitFileSource`] when `open` detects `<path>.000`,
+/// `<path>.001`, … parts on disk.
+pub enum FileSource {
+    Single(File),
+    Split(SplitFileSource),
+}
+
+impl BlockSource for FileSource {
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> Result<()> {
+        match self {
+            FileSource::Single(file) => file.read_at(offset, buf),
+            FileSource::Split(split) => split.read_at(offset, buf),
+        }
+    }
+
+    fn len(&self) -> u64 {
+        match self {
+            FileSource::Single(file) => BlockSource::len(file),
+            FileSource::Split(split) => split.len(),
+        }
+    }
+}
+
+/// Reads an SST file's footer and blocks out of a [`BlockSource`].
+///
+/// `SstReader` is generic over the underlying byte source so the same format
+/// logic works whether the bytes come from a file on disk, an in-memory
+/// buffer, a memory-mapped region (see `open_mmap`, behind the `mmap`
+/// feature), or (eventually) a ranged network fetch.
+pub struct SstReader<S: BlockSource> {
+    source: S,
     footer: Footer,
     file_size: u64,
+    read_options: ReadOptions,
+    /// Lazily-loaded, then cached, shared compression dictionary (the
+    /// `rocksdb.compression_dict` meta block). `None` means not yet looked
+    /// up; `Some(None)` means the table has no such block.
+    compression_dict: Option<Option<Vec<u8>>>,
+    /// Lazily-loaded, then cached, Bloom filter reader (the
+    /// `filter.yaledb.BuiltinBloomFilter` meta block). `None` means not yet
+    /// looked up; `Some(None)` means the table has no such block.
+    filter_block: Option<Option<FilterBlockReader>>,
 }
 
-impl SstReader {
+impl SstReader<FileSource> {
+    /// Open an SST file, transparently handling the case where it was
+    /// exported as a sequentially-numbered split set (`<path>.000`,
+    /// `<path>.001`, …) rather than a single file. Falls back to ordinary
+    /// single-file behavior when no split parts are found at `path`.
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let file = File::open(path)?;
-        let mut reader = BufReader::new(file);
+        Self::open_with_options(path, ReadOptions::default())
+    }
+
+    pub fn open_with_options<P: AsRef<Path>>(path: P, read_options: ReadOptions) -> Result<Self> {
+        let source = match SplitFileSource::probe(path.as_ref())? {
+            Some(split) => FileSource::Split(split),
+            None => FileSource::Single(File::open(path)?),
+        };
+
+        Self::from_source_with_options(source, read_options)
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl SstReader<crate::mmap_source::MmapFileSource> {
+    /// Open an SST file through an mmap-backed [`BlockSource`] instead of
+    /// [`SstReader::open`]'s plain `File` — see
+    /// [`crate::mmap_source::MmapFileSource`] for the tradeoffs. Unlike
+    /// `open`, this doesn't transparently handle split (`<path>.000`, …)
+    /// tables; mmap a single file.
+    pub fn open_mmap<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_mmap_with_options(path, ReadOptions::default())
+    }
 
-        let file_size = reader.seek(std::io::SeekFrom::End(0))?;
-        reader.seek(std::io::SeekFrom::Start(0))?;
+    pub fn open_mmap_with_options<P: AsRef<Path>>(
+        path: P,
+        read_options: ReadOptions,
+    ) -> Result<Self> {
+        let source = crate::mmap_source::MmapFileSource::open(path)?;
+        Self::from_source_with_options(source, read_options)
+    }
+}
+
+impl<S: BlockSource> SstReader<S> {
+    /// Build a reader directly from any [`BlockSource`], e.g. a `Vec<u8>`
+    /// already loaded into memory.
+    pub fn from_source(source: S) -> Result<Self> {
+        Self::from_source_with_options(source, ReadOptions::default())
+    }
 
-        let footer = Footer::read_from(&mut reader)?;
+    pub fn from_source_with_options(source: S, read_options: ReadOptions) -> Result<Self> {
+        let file_size = source.len();
+        let footer = Footer::read_from_source(&source)?;
 
         Ok(SstReader {
-            reader,
+            source,
             file_size,
             footer,
+            read_options,
+            compression_dict: None,
+            filter_block: None,
         })
     }
 
@@ -34,39 +124,306 @@ impl SstReader {
         &self.footer
     }
 
+    /// The `ReadOptions` this reader was constructed with — e.g. so
+    /// [`crate::iterator::SstEntryIterator`] can consult `merge_operator`
+    /// while folding consecutive same-key records.
+    pub fn read_options(&self) -> &ReadOptions {
+        &self.read_options
+    }
+
     pub fn file_size(&self) -> u64 {
         self.file_size
     }
 
-    pub(crate) fn read_block(&mut self, handle: BlockHandle) -> Result<Vec<u8>> {
-        if handle.offset + handle.size > self.file_size {
+    /// Read the block at `handle`, including its trailer if the footer's
+    /// magic number says this table's blocks carry one (see
+    /// [`Footer::has_block_trailers`]), verifying the trailer's checksum
+    /// against the algorithm declared in the footer when `verify` is set.
+    pub(crate) fn read_block(&mut self, handle: BlockHandle, verify: bool) -> Result<Vec<u8>> {
+        if verify && self.footer.has_block_trailers() {
+            return block_trailer::read_and_verify(&self.source, &handle, &self.footer);
+        }
+
+        let trailer_len = if self.footer.has_block_trailers() {
+            BLOCK_TRAILER_SIZE
+        } else {
+            0
+        };
+        let total_len = handle.size + trailer_len;
+        if handle.offset + total_len > self.file_size {
             return Err(Error::InvalidBlockHandle(
                 "Block extends beyond file size".to_string(),
             ));
         }
 
-        self.reader.seek(SeekFrom::Start(handle.offset))?;
-        let mut buffer = vec![0u8; handle.size as usize];
-        self.reader.read_exact(&mut buffer)?;
+        let mut buffer = vec![0u8; total_len as usize];
+        self.source.read_at(handle.offset, &mut buffer)?;
+
         Ok(buffer)
     }
 
-    pub fn read_data_block(
-        &mut self,
-        handle: BlockHandle,
-        compression_type: CompressionType,
-    ) -> Result<DataBlock> {
-        let block_data = self.read_block(handle)?;
-        DataBlock::new(&block_data, compression_type)
+    /// [`Self::read_block`] for the index block specifically, gated by
+    /// `ReadOptions::verify_index_blocks`. Exposed so
+    /// [`crate::iterator::SstTableIterator`] can read the index without
+    /// reaching into `read_options` itself.
+    pub(crate) fn read_index_block_bytes(&mut self, handle: BlockHandle) -> Result<Vec<u8>> {
+        self.read_block(handle, self.read_options.verify_index_blocks)
+    }
+
+    /// Read and decode the data block at `handle`, dispatching on the
+    /// compression id stored in the block's own trailer rather than a value
+    /// supplied by the caller, so mixed-compression tables decode correctly.
+    /// Ids outside the built-in [`CompressionType`] range are resolved
+    /// through `ReadOptions::registry` (see
+    /// [`crate::compression::decompress_by_id`]), so a table written by
+    /// another engine with a custom codec id can be read once that id is
+    /// registered. If the table has a shared compression dictionary meta
+    /// block, it's loaded (once, then cached) and used to decompress.
+    pub fn read_data_block(&mut self, handle: BlockHandle) -> Result<DataBlock> {
+        let block_data = self.read_block(handle, self.read_options.verify_data_blocks)?;
+        let compression_id = Self::trailer_compression_id(&block_data)?;
+        let dict = self.compression_dict()?;
+        DataBlock::new_with_dict_and_registry(
+            &block_data,
+            compression_id,
+            dict.as_deref(),
+            &self.read_options.registry,
+        )
+    }
+
+    pub fn read_data_block_reader(&mut self, handle: BlockHandle) -> Result<DataBlockReader> {
+        let block_data = self.read_block(handle, self.read_options.verify_data_blocks)?;
+        let compression_id = Self::trailer_compression_id(&block_data)?;
+        let dict = self.compression_dict()?;
+        DataBlockReader::new_with_dict_and_registry(
+            &block_data,
+            compression_id,
+            dict.as_deref(),
+            &self.read_options.registry,
+        )
     }
 
-    pub fn read_data_block_reader(
-        &mut self,
-        handle: BlockHandle,
-        compression_type: CompressionType,
-    ) -> Result<DataBlockReader> {
-        let block_data = self.read_block(handle)?;
-        DataBlockReader::new(&block_data, compression_type)
+    /// Load and decompress the shared compression dictionary meta block
+    /// (`rocksdb.compression_dict`) referenced from the metaindex, if the
+    /// table was written with one. Cached after the first call so repeated
+    /// data-block reads don't re-decode the metaindex every time.
+    fn compression_dict(&mut self) -> Result<Option<Vec<u8>>> {
+        if let Some(cached) = &self.compression_dict {
+            return Ok(cached.clone());
+        }
+
+        let metaindex_handle = self.footer.metaindex_handle.clone();
+        let metaindex_entries = self.read_block_entries(metaindex_handle)?;
+        let handle =
+            metaindex::find_metaindex_entry(&metaindex_entries, COMPRESSION_DICT_BLOCK_NAME)?;
+
+        let dict = match handle {
+            Some(handle) => {
+                let block_data = self.read_block(handle, self.read_options.verify_meta_blocks)?;
+                let compression_type = Self::trailer_compression_type(&block_data)?;
+                let content = &block_data[..block_data.len() - BLOCK_TRAILER_SIZE as usize];
+                Some(decompress(content, compression_type)?)
+            }
+            None => None,
+        };
+
+        self.compression_dict = Some(dict.clone());
+        Ok(dict)
+    }
+
+    /// Whether `key` could be present in the data block starting at
+    /// `block_offset`, consulting the table's Bloom filter meta block
+    /// (`filter.yaledb.BuiltinBloomFilter`) if one is present. Loaded once,
+    /// then cached, the same way [`Self::compression_dict`] is. Fails open
+    /// (returns `true`, i.e. "go check the data block") when the table has
+    /// no filter block at all, so callers can use this to skip a doomed
+    /// data-block read without risking a false negative.
+    pub(crate) fn filter_may_contain(&mut self, block_offset: u64, key: &[u8]) -> Result<bool> {
+        if self.filter_block.is_none() {
+            let metaindex_handle = self.footer.metaindex_handle.clone();
+            let metaindex_entries = self.read_block_entries(metaindex_handle)?;
+            let handle = metaindex::find_metaindex_entry(&metaindex_entries, FILTER_BLOCK_NAME)?;
+
+            let reader = match handle {
+                Some(handle) => {
+                    let block_data =
+                        self.read_block(handle, self.read_options.verify_filter_blocks)?;
+                    let compression_type = Self::trailer_compression_type(&block_data)?;
+                    let content = &block_data[..block_data.len() - BLOCK_TRAILER_SIZE as usize];
+                    let decompressed = decompress(content, compression_type)?;
+                    Some(FilterBlockReader::new(decompressed)?)
+                }
+                None => None,
+            };
+
+            self.filter_block = Some(reader);
+        }
+
+        Ok(match self.filter_block.as_ref().unwrap() {
+            Some(reader) => reader.key_may_match(block_offset, key),
+            None => true,
+        })
+    }
+
+    fn trailer_compression_type(block_data: &[u8]) -> Result<CompressionType> {
+        CompressionType::try_from(Self::trailer_compression_id(block_data)?)
+    }
+
+    /// The raw compression-id byte stored in `block_data`'s trailer, without
+    /// requiring it to be one of the built-in [`CompressionType`] variants —
+    /// see [`Self::read_data_block`].
+    fn trailer_compression_id(block_data: &[u8]) -> Result<u8> {
+        if block_data.len() < BLOCK_TRAILER_SIZE as usize {
+            return Err(Error::InvalidBlockFormat(
+                "Block too small to contain trailer".to_string(),
+            ));
+        }
+
+        Ok(block_data[block_data.len() - BLOCK_TRAILER_SIZE as usize])
+    }
+
+    /// Read and decode the entries of a meta block (metaindex or
+    /// properties) at `handle`, dispatching on its trailer compression type
+    /// and verifying its checksum per `ReadOptions::verify_meta_blocks`.
+    fn read_block_entries(&mut self, handle: BlockHandle) -> Result<Vec<KeyValue>> {
+        let block_data = self.read_block(handle, self.read_options.verify_meta_blocks)?;
+        let compression_type = Self::trailer_compression_type(&block_data)?;
+        let block = DataBlock::new(&block_data, compression_type)?;
+        block.get_entries()
+    }
+
+    /// Read and decode the metaindex block into a map from meta-block name
+    /// (`rocksdb.properties`, filter blocks, …) to its [`BlockHandle`].
+    pub fn metaindex(&mut self) -> Result<BTreeMap<String, BlockHandle>> {
+        let metaindex_handle = self.footer.metaindex_handle.clone();
+        let metaindex_entries = self.read_block_entries(metaindex_handle)?;
+        metaindex::decode_metaindex(&metaindex_entries)
+    }
+
+    /// Read the metaindex block and decode the `rocksdb.properties` entry it
+    /// points to.
+    pub fn table_properties(&mut self) -> Result<TableProperties> {
+        let metaindex_handle = self.footer.metaindex_handle.clone();
+        let metaindex_entries = self.read_block_entries(metaindex_handle)?;
+
+        let properties_handle =
+            metaindex::find_metaindex_entry(&metaindex_entries, PROPERTIES_BLOCK_NAME)?
+                .ok_or_else(|| {
+                    Error::DataCorruption(
+                        "Metaindex has no rocksdb.properties entry".to_string(),
+                    )
+                })?;
+
+        let properties_entries = self.read_block_entries(properties_handle)?;
+        TableProperties::from_entries(&properties_entries)
+    }
+
+    /// Verify the table against its optional BLAKE3 whole-file integrity
+    /// digest (see [`crate::integrity`]), a cryptographic layer on top of
+    /// the existing per-block RocksDB-compatible checksums. Returns `None`
+    /// if the table has no `rocksdb.blake3_integrity` meta block — nothing
+    /// to verify against — or `Some(matches)` once one is found.
+    ///
+    /// Only supported for `format_version < 6`: v6+ footers in this crate
+    /// carry a null `index_handle` (see [`Footer`]'s `decode_from_bytes`),
+    /// so there's no reliable end-of-data-region boundary to hash against
+    /// here yet.
+    pub fn verify_file_integrity(&mut self) -> Result<Option<bool>> {
+        if self.footer.format_version >= 6 {
+            return Err(Error::UnsupportedOperation(
+                "verify_file_integrity requires a real index_handle, but format_version >= 6 \
+                 footers store a null one in this crate"
+                    .to_string(),
+            ));
+        }
+
+        let metaindex_handle = self.footer.metaindex_handle.clone();
+        let metaindex_entries = self.read_block_entries(metaindex_handle)?;
+        let Some(handle) =
+            metaindex::find_metaindex_entry(&metaindex_entries, FILE_INTEGRITY_BLOCK_NAME)?
+        else {
+            return Ok(None);
+        };
+
+        let block_data = self.read_block(handle, self.read_options.verify_meta_blocks)?;
+        let compression_type = Self::trailer_compression_type(&block_data)?;
+        let content = &block_data[..block_data.len() - BLOCK_TRAILER_SIZE as usize];
+        let stored_bytes = decompress(content, compression_type)?;
+        let stored_digest = FileIntegrityDigest::decode_from_bytes(&stored_bytes)?;
+
+        let index_handle = &self.footer.index_handle;
+        let region_len = index_handle.offset + index_handle.size + BLOCK_TRAILER_SIZE;
+        let mut region = vec![0u8; region_len as usize];
+        self.source.read_at(0, &mut region)?;
+
+        Ok(Some(stored_digest.matches(&region)))
+    }
+
+    /// Walk every data block reachable from the index, verifying each
+    /// block's checksum, confirming handle bounds stay within the file, and
+    /// cross-checking the total entry count against the table properties'
+    /// `num_entries` when a properties block is present. A redump-style
+    /// integrity sweep that reports problems rather than panicking.
+    pub fn validate(&mut self) -> Result<ValidationReport> {
+        let mut report = ValidationReport::default();
+
+        let index_handle = self.footer.index_handle.clone();
+        let index_data = self.read_index_block_bytes(index_handle)?;
+        // `read_index_block_bytes` already verifies the trailer (via
+        // `ReadOptions::verify_index_blocks`) using the footer-aware,
+        // context-checksum-capable path, so re-check with the plain
+        // algorithm here would be redundant at best and wrong for
+        // format_version >= 6 at worst.
+        let index_block = IndexBlock::new_unchecked(&index_data, CompressionType::None)?;
+        let handles = index_block.get_all_block_handles()?;
+
+        for handle in &handles {
+            if handle.offset + handle.size + BLOCK_TRAILER_SIZE > self.file_size {
+                report.errors.push(format!(
+                    "Block at offset {} size {} extends beyond file size {}",
+                    handle.offset, handle.size, self.file_size
+                ));
+                continue;
+            }
+
+            match self.read_data_block(handle.clone()) {
+                Ok(block) => {
+                    report.blocks_checked += 1;
+                    report.entries_checked += block.num_entries() as u64;
+                }
+                Err(e) => {
+                    report.errors.push(format!(
+                        "Block at offset {} failed to decode: {}",
+                        handle.offset, e
+                    ));
+                }
+            }
+        }
+
+        if let Ok(properties) = self.table_properties() {
+            if properties.num_entries != 0 && properties.num_entries != report.entries_checked {
+                report.errors.push(format!(
+                    "Entry count mismatch: table properties report {} entries, but {} were found",
+                    properties.num_entries, report.entries_checked
+                ));
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// Report produced by [`SstReader::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub blocks_checked: usize,
+    pub entries_checked: u64,
+    pub errors: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
     }
 }
 
@@ -190,6 +547,292 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_compression_dict_is_loaded_and_cached() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::types::ChecksumType;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let dict_handle = image.add_raw_block(b"a shared dictionary blob")?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![0])])?;
+        let metaindex = image.add_metaindex(&[
+            (COMPRESSION_DICT_BLOCK_NAME, dict_handle),
+            (PROPERTIES_BLOCK_NAME, properties),
+        ])?;
+        let bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        let mut reader = SstReader::from_source(bytes)?;
+        assert_eq!(reader.compression_dict()?, Some(b"a shared dictionary blob".to_vec()));
+
+        // A second call must hit the cache rather than re-reading the
+        // metaindex; there's no handle left to the builder to assert on
+        // directly, so just confirm the cached value is stable.
+        assert_eq!(reader.compression_dict()?, Some(b"a shared dictionary blob".to_vec()));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_compression_dict_is_none_when_table_has_no_dict_block() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::types::ChecksumType;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![0])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        let mut reader = SstReader::from_source(bytes)?;
+        assert_eq!(reader.compression_dict()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_verify_data_blocks_flag_gates_data_block_checksum() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::types::ChecksumType;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data = image.add_block(&[(b"key".to_vec(), b"value".to_vec())])?;
+        let metaindex = image.add_metaindex(&[])?;
+        let mut bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        // Flip a byte inside the data block's checksummed content without
+        // touching anything else, corrupting its trailer checksum.
+        let corrupt_at = data.offset as usize;
+        bytes[corrupt_at] ^= 0xff;
+
+        let mut verifying = SstReader::from_source(bytes.clone())?;
+        assert!(verifying.read_data_block(data.clone()).is_err());
+
+        let lenient_options = ReadOptions {
+            verify_data_blocks: false,
+            ..ReadOptions::default()
+        };
+        let mut lenient = SstReader::from_source_with_options(bytes, lenient_options)?;
+        assert!(lenient.read_data_block(data).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_verify_file_integrity_detects_tamper() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::integrity::FileIntegrityDigest;
+        use crate::types::ChecksumType;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let _data = image.add_block(&[(b"key".to_vec(), b"value".to_vec())])?;
+        // Stand in for the real index block: what matters for this test is
+        // only that `index` is the handle marking the end of the data/index
+        // region the digest covers.
+        let index = image.add_block(&[(b"idx".to_vec(), b"handle".to_vec())])?;
+        let region_len = (index.offset + index.size + BLOCK_TRAILER_SIZE) as usize;
+        let digest = FileIntegrityDigest::compute(&image.bytes_so_far()[..region_len]);
+
+        let digest_handle = image.add_raw_block(&digest.encode_to_bytes())?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[
+            (FILE_INTEGRITY_BLOCK_NAME, digest_handle),
+            (PROPERTIES_BLOCK_NAME, properties),
+        ])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let mut reader = SstReader::from_source(bytes.clone())?;
+        assert_eq!(reader.verify_file_integrity()?, Some(true));
+
+        let mut tampered = bytes;
+        tampered[0] ^= 0xff;
+        let mut tampered_reader = SstReader::from_source(tampered)?;
+        assert_eq!(tampered_reader.verify_file_integrity()?, Some(false));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_filter_may_contain_is_loaded_and_cached() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::filter_block::{FilterBlockBuilder, DEFAULT_BITS_PER_KEY, FILTER_BLOCK_NAME};
+        use crate::types::{ChecksumType, CompressionType};
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data = image.add_block(&[(b"key".to_vec(), b"value".to_vec())])?;
+
+        let mut filter_builder = FilterBlockBuilder::new(DEFAULT_BITS_PER_KEY);
+        filter_builder.start_block(data.offset);
+        filter_builder.add_key(b"key");
+        let filter_bytes = filter_builder.finish(CompressionType::None, ChecksumType::CRC32c, None, None)?;
+        let filter_handle = image.add_finished_block(&filter_bytes);
+
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[
+            (FILTER_BLOCK_NAME, filter_handle),
+            (PROPERTIES_BLOCK_NAME, properties),
+        ])?;
+        let bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        let mut reader = SstReader::from_source(bytes)?;
+        assert!(reader.filter_may_contain(data.offset, b"key")?);
+        assert!(!reader.filter_may_contain(data.offset, b"definitely-absent")?);
+
+        // A second call must hit the cache rather than re-reading the
+        // metaindex; there's no handle left to the builder to assert on
+        // directly, so just confirm the cached value is stable.
+        assert!(reader.filter_may_contain(data.offset, b"key")?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_filter_may_contain_fails_open_without_a_filter_block() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::types::ChecksumType;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data = image.add_block(&[(b"key".to_vec(), b"value".to_vec())])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        let mut reader = SstReader::from_source(bytes)?;
+        assert!(reader.filter_may_contain(data.offset, b"anything")?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_read_data_block_resolves_a_custom_compression_id_via_the_registry() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::compressor::{Compressor, CompressorRegistry};
+        use crate::types::ChecksumType;
+
+        // Elementwise and length-preserving, so the raw trailer appended
+        // after compression inverts back to garbage that gets discarded
+        // rather than corrupting the real content (see the identical
+        // caveat on `data_block::tests::Xor`).
+        struct Xor(u8);
+
+        impl Compressor for Xor {
+            fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+                Ok(data.iter().map(|b| b ^ self.0).collect())
+            }
+
+            fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+                self.compress(data)
+            }
+        }
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data = image.add_block_with_id(
+            &[(b"key".to_vec(), b"value".to_vec())],
+            200,
+            Xor(0x42),
+        )?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        let mut registry = CompressorRegistry::new();
+        registry.register(200, Xor(0x42));
+        let read_options = ReadOptions {
+            registry,
+            ..ReadOptions::default()
+        };
+
+        let mut reader = SstReader::from_source_with_options(bytes, read_options)?;
+        let block = reader.read_data_block(data)?;
+        let entries = block.get_entries()?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, b"key");
+        assert_eq!(entries[0].value, b"value");
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_read_data_block_fails_for_an_unregistered_custom_compression_id() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::compressor::Compressor;
+        use crate::types::ChecksumType;
+
+        struct Xor(u8);
+
+        impl Compressor for Xor {
+            fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+                Ok(data.iter().map(|b| b ^ self.0).collect())
+            }
+
+            fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+                self.compress(data)
+            }
+        }
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let data = image.add_block_with_id(
+            &[(b"key".to_vec(), b"value".to_vec())],
+            200,
+            Xor(0x42),
+        )?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        let mut reader = SstReader::from_source(bytes)?;
+        assert!(matches!(
+            reader.read_data_block(data),
+            Err(Error::UnsupportedCompressionType(200))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_verify_file_integrity_is_none_without_a_digest_block() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::types::ChecksumType;
+
+        let mut image = SstImageBuilder::new(5, ChecksumType::CRC32c);
+        let index = image.add_block(&[(b"key".to_vec(), b"value".to_vec())])?;
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, index)?;
+
+        let mut reader = SstReader::from_source(bytes)?;
+        assert_eq!(reader.verify_file_integrity()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "test-support")]
+    fn test_verify_file_integrity_rejects_v6_footers() -> Result<()> {
+        use crate::builder::SstImageBuilder;
+        use crate::types::ChecksumType;
+
+        let mut image = SstImageBuilder::new(6, ChecksumType::CRC32c);
+        let properties = image.add_block(&[(b"rocksdb.num.entries".to_vec(), vec![1])])?;
+        let metaindex = image.add_metaindex(&[(PROPERTIES_BLOCK_NAME, properties)])?;
+        let bytes = image.finish(metaindex, BlockHandle::null())?;
+
+        let mut reader = SstReader::from_source(bytes)?;
+        assert!(matches!(
+            reader.verify_file_integrity(),
+            Err(Error::UnsupportedOperation(_))
+        ));
+
+        Ok(())
+    }
+
     // #[test]
     // fn test_read_data_blocks_format_v5() {
     //     use crate::data_block::DataBlock;
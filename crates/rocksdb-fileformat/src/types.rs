@@ -72,6 +72,126 @@ impl ChecksumType {
     }
 }
 
+/// Derive the per-position checksum modifier format_version >= 6 ("context
+/// checksums") adds on top of a plain [`ChecksumType::calculate`] result, so
+/// the stored checksum is bound to where the block or footer actually sits
+/// in the file rather than just its contents — swapping two same-sized
+/// blocks becomes detectable. Mirrors RocksDB's `ChecksumModifierForContext`:
+/// the low 32 bits of the absolute file `offset`, combined with the file's
+/// `base_context_checksum`.
+pub fn checksum_modifier_for_context(base_context_checksum: u32, offset: u64) -> u32 {
+    let offset_digest = offset as u32;
+    offset_digest.wrapping_add(base_context_checksum)
+}
+
+impl ChecksumType {
+    /// `calculate`, plus the format_version >= 6 context-checksum modifier
+    /// for a block or footer living at `block_offset` in a file whose footer
+    /// carries `base_context_checksum`. Callers on format_version < 6 should
+    /// keep calling [`ChecksumType::calculate`] directly instead — there is
+    /// no file-offset binding to apply.
+    pub fn calculate_with_context(
+        self,
+        data: &[u8],
+        base_context_checksum: u32,
+        block_offset: u64,
+    ) -> u32 {
+        self.calculate(data)
+            .wrapping_add(checksum_modifier_for_context(base_context_checksum, block_offset))
+    }
+}
+
+/// Incremental counterpart to [`ChecksumType::calculate`], for hashing a
+/// block's content as it's produced instead of buffering the whole thing
+/// into one contiguous slice first. Feeding the same bytes through one or
+/// more [`Self::update`] calls and then [`Self::finalize`] always produces
+/// the same result `calculate` would on the concatenation of those bytes.
+pub enum ChecksumHasher {
+    None,
+    CRC32c(u32),
+    Hash(xxhash_rust::xxh32::Xxh32),
+    Hash64(xxhash_rust::xxh64::Xxh64),
+    /// XXH3 (see [`ChecksumType::calculate`]) hashes every byte except the
+    /// last through the streaming state, then folds the held-back last byte
+    /// in at `finalize` via `ModifyChecksumForLastByte`. The streaming XXH3
+    /// API has no hook for "the last byte of the whole stream" (it doesn't
+    /// know which `update` call is the final one), so the most recently
+    /// seen byte is always staged here instead of fed to `state`
+    /// immediately — `update` only commits a byte to `state` once it's
+    /// learned a later byte exists to take its place as the new pending one.
+    XXH3 {
+        state: xxhash_rust::xxh3::Xxh3,
+        pending_last_byte: Option<u8>,
+    },
+}
+
+impl ChecksumHasher {
+    pub fn new(checksum_type: ChecksumType) -> Self {
+        match checksum_type {
+            ChecksumType::None => ChecksumHasher::None,
+            ChecksumType::CRC32c => ChecksumHasher::CRC32c(0),
+            ChecksumType::Hash => ChecksumHasher::Hash(xxhash_rust::xxh32::Xxh32::new(0)),
+            ChecksumType::Hash64 => ChecksumHasher::Hash64(xxhash_rust::xxh64::Xxh64::new(0)),
+            ChecksumType::XXH3 => ChecksumHasher::XXH3 {
+                state: xxhash_rust::xxh3::Xxh3::new(),
+                pending_last_byte: None,
+            },
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+
+        match self {
+            ChecksumHasher::None => {}
+            ChecksumHasher::CRC32c(crc) => *crc = crc32c::crc32c_append(*crc, data),
+            ChecksumHasher::Hash(state) => state.update(data),
+            ChecksumHasher::Hash64(state) => state.update(data),
+            ChecksumHasher::XXH3 {
+                state,
+                pending_last_byte,
+            } => {
+                if let Some(byte) = pending_last_byte.take() {
+                    state.update(&[byte]);
+                }
+
+                let (to_hash, new_pending) = data.split_at(data.len() - 1);
+                state.update(to_hash);
+                *pending_last_byte = Some(new_pending[0]);
+            }
+        }
+    }
+
+    /// Consume the hasher, producing the same `u32` [`ChecksumType::calculate`]
+    /// would return for the concatenation of every `update`d slice.
+    pub fn finalize(self) -> u32 {
+        match self {
+            ChecksumHasher::None => 0,
+            ChecksumHasher::CRC32c(crc) => {
+                // Apply RocksDB CRC32c masking: rotate right by 15 bits and add constant
+                const MASK_DELTA: u32 = 0xa282ead8;
+                ((crc >> 15) | (crc << 17)).wrapping_add(MASK_DELTA)
+            }
+            ChecksumHasher::Hash(state) => state.digest(),
+            ChecksumHasher::Hash64(state) => (state.digest() & 0xFFFFFFFF) as u32,
+            ChecksumHasher::XXH3 {
+                state,
+                pending_last_byte,
+            } => match pending_last_byte {
+                // No bytes were ever seen, matching calculate's own empty-input case.
+                None => 0,
+                Some(last_byte) => {
+                    let v = (state.digest() & 0xFFFFFFFF) as u32;
+                    const RANDOM_PRIME: u32 = 0x6b9083d9;
+                    v ^ (last_byte as u32).wrapping_mul(RANDOM_PRIME)
+                }
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompressionType {
     None = 0,
@@ -129,6 +249,23 @@ pub struct WriteOptions {
     pub block_size: usize,
     pub block_restart_interval: usize,
     pub format_version: FormatVersion,
+    /// Target size in bytes of a shared compression dictionary for the
+    /// `rocksdb.compression_dict` meta block (see
+    /// [`crate::compression::train_zstd_dictionary`]). `0` means no
+    /// dictionary. [`crate::sst_file_writer::SstFileWriter`] does not read
+    /// this field: it trains no dictionary and writes no
+    /// `rocksdb.compression_dict` block, whatever the value.
+    pub compression_dict_size: usize,
+    /// Total bytes of early block samples for training the compression
+    /// dictionary. [`crate::sst_file_writer::SstFileWriter`] does not read
+    /// this field.
+    pub compression_dict_sample_budget: usize,
+    /// Opt-in BLAKE3 digest (see [`crate::integrity`]) over the table's
+    /// data/index region, kept in the `rocksdb.blake3_integrity` meta block
+    /// on top of the per-block RocksDB-style checksums. Disabled by default.
+    /// [`crate::sst_file_writer::SstFileWriter`] does not read this field:
+    /// it writes no digest block, whatever the value.
+    pub enable_file_integrity_digest: bool,
 }
 
 impl Default for WriteOptions {
@@ -138,26 +275,73 @@ impl Default for WriteOptions {
             block_size: DEFAULT_BLOCK_SIZE,
             block_restart_interval: DEFAULT_BLOCK_RESTART_INTERVAL,
             format_version: FormatVersion::V5,
+            compression_dict_size: 0,
+            compression_dict_sample_budget: 0,
+            enable_file_integrity_digest: false,
         }
     }
 }
 
 /// Configuration options for reading SST files
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ReadOptions {
-    /// Whether to verify checksums when reading the file.
-    /// Enabled by default for data integrity protection across all format versions.
-    pub verify_checksums: bool,
+    /// Verify data block checksums against their trailers.
+    pub verify_data_blocks: bool,
+    /// Verify index block checksums against their trailers.
+    pub verify_index_blocks: bool,
+    /// Verify filter block checksums against their trailers before
+    /// consulting the Bloom filter (`filter.yaledb.BuiltinBloomFilter`, see
+    /// [`crate::filter_block`]) in [`crate::sst_reader::SstReader::filter_may_contain`].
+    pub verify_filter_blocks: bool,
+    /// Verify checksums on the metaindex, properties, compression
+    /// dictionary, and any other meta blocks.
+    pub verify_meta_blocks: bool,
+    /// Codecs for data block compression ids outside the built-in
+    /// [`CompressionType`] range, consulted by
+    /// [`crate::sst_reader::SstReader::read_data_block`]/
+    /// [`crate::sst_reader::SstReader::read_data_block_reader`] (see
+    /// [`crate::compression::decompress_by_id`]). Empty by default; register
+    /// a codec here to read a foreign engine's SST files that use a
+    /// compression id this crate doesn't otherwise know.
+    pub registry: crate::compressor::CompressorRegistry,
+    /// Resolves `EntryType::Merge` records against the base record (and any
+    /// other merge operands) that precede them for the same key, consulted
+    /// by [`crate::iterator::SstEntryIterator::find`]/
+    /// [`crate::iterator::SstEntryIterator::collect_all`]. `None` by
+    /// default, in which case those methods don't decode each entry's
+    /// [`crate::sst_file_writer::EntryType`] prefix at all and simply
+    /// return the raw stored value, as before.
+    pub merge_operator: Option<std::sync::Arc<dyn crate::merge::MergeOperator>>,
 }
 
 impl Default for ReadOptions {
     fn default() -> Self {
+        // Verify everything by default, for data integrity protection
+        // across all format versions and block categories.
         ReadOptions {
-            verify_checksums: true,
+            verify_data_blocks: true,
+            verify_index_blocks: true,
+            verify_filter_blocks: true,
+            verify_meta_blocks: true,
+            registry: crate::compressor::CompressorRegistry::new(),
+            merge_operator: None,
         }
     }
 }
 
+impl std::fmt::Debug for ReadOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadOptions")
+            .field("verify_data_blocks", &self.verify_data_blocks)
+            .field("verify_index_blocks", &self.verify_index_blocks)
+            .field("verify_filter_blocks", &self.verify_filter_blocks)
+            .field("verify_meta_blocks", &self.verify_meta_blocks)
+            .field("registry", &self.registry)
+            .field("merge_operator", &self.merge_operator.is_some())
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -493,4 +677,95 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_checksum_modifier_for_context_binds_to_offset() {
+        // Same base context checksum at different offsets must produce
+        // different modifiers, or block-swap corruption would go undetected.
+        let base = 0x12345678;
+        let at_zero = checksum_modifier_for_context(base, 0);
+        let at_one = checksum_modifier_for_context(base, 1);
+        assert_ne!(at_zero, at_one);
+
+        // Only the low 32 bits of the offset matter.
+        let at_wrapped = checksum_modifier_for_context(base, (1u64 << 32) + 1);
+        assert_eq!(at_one, at_wrapped);
+    }
+
+    #[test]
+    fn test_calculate_with_context_matches_manual_modifier() {
+        let data = b"hello world";
+        let base_context_checksum = 0xdeadbeef;
+        let block_offset = 4096;
+
+        let expected = ChecksumType::XXH3
+            .calculate(data)
+            .wrapping_add(checksum_modifier_for_context(base_context_checksum, block_offset));
+        let actual =
+            ChecksumType::XXH3.calculate_with_context(data, base_context_checksum, block_offset);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_calculate_with_context_zero_base_still_binds_to_offset() {
+        // base_context_checksum == 0 does not make the modifier itself zero
+        // (the offset alone still perturbs it) — only skipping the context
+        // check entirely on format_version < 6 does that, which is handled
+        // by callers choosing `calculate` instead of `calculate_with_context`.
+        let data = b"hello world";
+        let at_offset_0 = ChecksumType::CRC32c.calculate_with_context(data, 0, 0);
+        let at_offset_1 = ChecksumType::CRC32c.calculate_with_context(data, 0, 1);
+        assert_eq!(at_offset_0, ChecksumType::CRC32c.calculate(data));
+        assert_ne!(at_offset_0, at_offset_1);
+    }
+
+    const ALL_CHECKSUM_TYPES: &[ChecksumType] = &[
+        ChecksumType::None,
+        ChecksumType::CRC32c,
+        ChecksumType::Hash,
+        ChecksumType::Hash64,
+        ChecksumType::XXH3,
+    ];
+
+    #[test]
+    fn test_checksum_hasher_matches_calculate_on_empty_input() {
+        for checksum_type in ALL_CHECKSUM_TYPES {
+            let hasher = ChecksumHasher::new(*checksum_type);
+            assert_eq!(hasher.finalize(), checksum_type.calculate(&[]));
+        }
+    }
+
+    #[test]
+    fn test_checksum_hasher_matches_calculate_single_update() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        for checksum_type in ALL_CHECKSUM_TYPES {
+            let mut hasher = ChecksumHasher::new(*checksum_type);
+            hasher.update(data);
+            assert_eq!(hasher.finalize(), checksum_type.calculate(data));
+        }
+    }
+
+    #[test]
+    fn test_checksum_hasher_matches_calculate_single_byte_input() {
+        for checksum_type in ALL_CHECKSUM_TYPES {
+            let mut hasher = ChecksumHasher::new(*checksum_type);
+            hasher.update(&[0x41]);
+            assert_eq!(hasher.finalize(), checksum_type.calculate(&[0x41]));
+        }
+    }
+
+    #[test]
+    fn test_checksum_hasher_matches_calculate_across_chunk_boundaries() {
+        // Feeding the same bytes through many small `update` calls instead
+        // of one contiguous slice is what exercises XXH3's held-back
+        // last-byte logic across boundaries.
+        let data = b"The quick brown fox jumps over the lazy dog";
+        for checksum_type in ALL_CHECKSUM_TYPES {
+            let mut hasher = ChecksumHasher::new(*checksum_type);
+            for chunk in data.chunks(3) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), checksum_type.calculate(data));
+        }
+    }
 }